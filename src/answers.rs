@@ -0,0 +1,288 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::PathBuf,
+};
+
+use crate::report::{Outcome, Report};
+
+pub const ANSWER_DIR: &str = "answers";
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    Grid(Grid),
+}
+
+impl Answer {
+    /// Whether the answer needs lines of its own instead of sharing one with
+    /// its label.
+    pub fn is_multiline(&self) -> bool {
+        match self {
+            Answer::Integer(_) => false,
+            Answer::Text(text) => text.contains('\n'),
+            Answer::Grid(_) => true,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(grid) => write!(f, "{}", grid),
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(value: $integer) -> Self {
+                    Answer::Integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+integer_answer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+impl From<Grid> for Answer {
+    fn from(value: Grid) -> Self {
+        Answer::Grid(value)
+    }
+}
+
+/// Answers compare equal to their displayed form, which is also how the
+/// known-good answers are stored.
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Answer::Integer(value) => other.parse() == Ok(*value),
+            Answer::Text(text) => text == other,
+            Answer::Grid(grid) => grid.to_string() == other,
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<String> for Answer {
+    fn eq(&self, other: &String) -> bool {
+        self == other.as_str()
+    }
+}
+
+/// Lit and dark pixels, for answers that are drawn as letters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub width: usize,
+    pub pixels: Vec<bool>,
+}
+
+impl Grid {
+    /// The rows drawn with `#` for lit and `.` for dark pixels.
+    pub fn rows(&self) -> impl Iterator<Item = String> + '_ {
+        self.pixels
+            .chunks(self.width.max(1))
+            .map(|row| row.iter().map(|lit| if *lit { '#' } else { '.' }).collect())
+    }
+}
+
+/// Reads rows of `#` and `.`, padding short rows with dark pixels.
+impl From<&str> for Grid {
+    fn from(value: &str) -> Self {
+        let width = value.lines().map(|line| line.len()).max().unwrap_or(0);
+
+        let pixels = value
+            .lines()
+            .flat_map(|line| {
+                line.chars()
+                    .map(|char| char == '#')
+                    .chain(std::iter::repeat(false))
+                    .take(width)
+            })
+            .collect();
+
+        Self { width, pixels }
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{}", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What the runner does with the known-good answers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnswerMode {
+    #[default]
+    Off,
+    Verify,
+    Record,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong(String),
+    Unknown,
+}
+
+impl Verdict {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Wrong(_) => "wrong",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+/// Known-good answers, stored as one `dayNptP.txt` file per part so that
+/// multiline answers need no escaping.
+pub struct AnswerStore {
+    pub directory: PathBuf,
+}
+
+impl AnswerStore {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    fn path(&self, day: u8, part: u8) -> PathBuf {
+        self.directory.join(format!("day{}pt{}.txt", day, part))
+    }
+
+    pub fn expected(&self, day: u8, part: u8) -> Option<String> {
+        let content = fs::read_to_string(self.path(day, part)).ok()?;
+
+        Some(content.trim_end_matches(['\n', '\r']).to_string())
+    }
+
+    pub fn verdict(&self, day: u8, part: u8, answer: &Answer) -> Verdict {
+        match self.expected(day, part) {
+            Some(expected) if *answer == expected => Verdict::Correct,
+            Some(expected) => Verdict::Wrong(expected),
+            None => Verdict::Unknown,
+        }
+    }
+
+    /// Attaches a verdict to every solved report.
+    pub fn verify(&self, reports: &mut [Report]) {
+        for report in reports {
+            if let Outcome::Solved(answer) = &report.outcome {
+                report.verdict = Some(self.verdict(report.day, report.part, answer));
+            }
+        }
+    }
+
+    /// Writes the answer of every solved report, returning how many were
+    /// written.
+    pub fn record(&self, reports: &[Report]) -> Result<usize, String> {
+        fs::create_dir_all(&self.directory)
+            .map_err(|error| format!("cannot create {}: {}", self.directory.display(), error))?;
+
+        let mut recorded = 0;
+
+        for report in reports {
+            if let Outcome::Solved(answer) = &report.outcome {
+                let path = self.path(report.day, report.part);
+
+                fs::write(&path, format!("{}\n", answer))
+                    .map_err(|error| format!("cannot write {}: {}", path.display(), error))?;
+
+                recorded += 1;
+            }
+        }
+
+        Ok(recorded)
+    }
+}
+
+/// Line by line comparison of an expected and an actual answer, with `-`
+/// marking expected lines and `+` the actual ones.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let mut output = String::new();
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    for index in 0..expected.len().max(actual.len()) {
+        match (expected.get(index), actual.get(index)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                output += &format!("  {}\n", expected);
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    output += &format!("- {}\n", expected);
+                }
+                if let Some(actual) = actual {
+                    output += &format!("+ {}\n", actual);
+                }
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, Answer, Grid};
+
+    #[test]
+    fn answers_compare_to_stored_text() {
+        assert_eq!(Answer::from(24000u64), "24000");
+        assert_eq!(Answer::from(24000u64), Answer::Integer(24000));
+        assert_ne!(Answer::from(24000u64), "24001");
+        assert_eq!(Answer::from("CMZ"), "CMZ");
+        assert!(!Answer::from("CMZ").is_multiline());
+
+        let grid = Grid::from("#..#\n.##");
+
+        assert_eq!(grid.width, 4);
+        assert_eq!(grid.pixels.len(), 8);
+        assert_eq!(grid.to_string(), "#..#\n.##.");
+
+        let answer = Answer::from(grid);
+
+        assert!(answer.is_multiline());
+        assert_eq!(answer, "#..#\n.##.");
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        assert_eq!(diff("24000", "24001"), "- 24000\n+ 24001\n");
+        assert_eq!(diff("#.\n.#", "#.\n##\n.."), "  #.\n- .#\n+ ##\n+ ..\n");
+    }
+}
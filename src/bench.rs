@@ -0,0 +1,160 @@
+use std::{
+    fmt::Write,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::solver::{self, Cancel, Puzzle, Solver};
+
+pub const WARMUP: usize = 3;
+pub const RUNS: usize = 10;
+
+const COLUMN_WIDTH: usize = 13;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "statistics need at least one sample");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let runs = sorted.len();
+        let nanos: Vec<f64> = sorted
+            .iter()
+            .map(|sample| sample.as_nanos() as f64)
+            .collect();
+
+        let median = if runs.is_multiple_of(2) {
+            (nanos[runs / 2 - 1] + nanos[runs / 2]) / 2.0
+        } else {
+            nanos[runs / 2]
+        };
+
+        let mean = nanos.iter().sum::<f64>() / runs as f64;
+
+        let variance = if runs > 1 {
+            nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (runs - 1) as f64
+        } else {
+            0.0
+        };
+
+        // Nearest rank, so the percentile is always one of the samples
+        let p95_rank = ((runs as f64 * 0.95).ceil() as usize).max(1);
+
+        Self {
+            runs,
+            min: sorted[0],
+            median: Duration::from_nanos(median.round() as u64),
+            mean: Duration::from_nanos(mean.round() as u64),
+            p95: sorted[p95_rank - 1],
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+}
+
+/// Times `runs` sequential solves of one part of an already parsed puzzle
+/// after `warmup` untimed ones, stopping at the first panic or error.
+pub fn measure(
+    solver: &dyn Solver,
+    part: u8,
+    puzzle: &Puzzle,
+    warmup: usize,
+    runs: usize,
+) -> Result<Stats, String> {
+    let cancel = Cancel::never();
+    let solve = || {
+        solver::isolate(|| solver.solve(part, black_box(puzzle), &cancel))?
+            .map_err(|error| error.to_string())
+    };
+
+    for _ in 0..warmup {
+        black_box(solve()?);
+    }
+
+    let samples = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(solve()?);
+            Ok(start.elapsed())
+        })
+        .collect::<Result<Vec<Duration>, String>>()?;
+
+    Ok(Stats::from_samples(&samples))
+}
+
+pub fn header() -> String {
+    let mut output = format!("{:<10}", "");
+
+    for column in ["min", "median", "mean", "p95", "std dev"] {
+        write!(output, "{:>width$}", column, width = COLUMN_WIDTH).unwrap();
+    }
+
+    output
+}
+
+pub fn row(day: u8, part: u8, result: &Result<Stats, String>) -> String {
+    let mut output = format!("{:<10}", format!("day{}pt{}", day, part));
+
+    match result {
+        Ok(stats) => {
+            for value in [
+                stats.min,
+                stats.median,
+                stats.mean,
+                stats.p95,
+                stats.std_dev,
+            ] {
+                write!(
+                    output,
+                    "{:>width$}",
+                    format!("{:.2?}", value),
+                    width = COLUMN_WIDTH
+                )
+                .unwrap();
+            }
+        }
+        Err(error) => write!(output, "{:>width$}", error, width = COLUMN_WIDTH).unwrap(),
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Stats;
+
+    #[test]
+    fn stats_from_samples() {
+        let samples: Vec<Duration> = [5, 1, 4, 2, 3, 100]
+            .into_iter()
+            .map(Duration::from_nanos)
+            .collect();
+
+        let stats = Stats::from_samples(&samples);
+
+        assert_eq!(stats.runs, 6);
+        assert_eq!(stats.min, Duration::from_nanos(1));
+        assert_eq!(stats.median, Duration::from_nanos(4));
+        assert_eq!(stats.mean, Duration::from_nanos(19));
+        assert_eq!(stats.p95, Duration::from_nanos(100));
+        assert_eq!(stats.std_dev, Duration::from_nanos(40));
+
+        let single = Stats::from_samples(&[Duration::from_nanos(7)]);
+
+        assert_eq!(single.median, Duration::from_nanos(7));
+        assert_eq!(single.p95, Duration::from_nanos(7));
+        assert_eq!(single.std_dev, Duration::ZERO);
+    }
+}
@@ -0,0 +1,547 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    answers::{AnswerMode, ANSWER_DIR},
+    bench,
+    extra::Sink,
+    history::{HISTORY_FILE, THRESHOLD},
+    input::Source,
+    report::Format,
+    solver::{Selection, DAYS, PARTS},
+};
+
+pub const USAGE: &str = "\
+Usage: aoc2022 [bench|compare] [OPTIONS] [DAYS]...
+       aoc2022 new-day <DAY>
+
+Commands:
+  (none)             solve the selected parts in parallel, exiting with
+                     status 1 when a part fails to load or panics
+  bench              time the selected parts one at a time, reporting
+                     min, median, mean, p95 and standard deviation, and
+                     append the results to the benchmark history
+  compare            compare the median times of two runs in the
+                     benchmark history and exit with status 1 when a
+                     part got slower than the threshold allows
+  new-day            create src/dayN.rs from a template with empty inputs,
+                     and register it with the solvers and the example
+                     cases, refusing to overwrite existing files
+
+Arguments:
+  [DAYS]...          days to run: a day `7`, a range `3-7`, a list `1,4,9` or `all`
+
+Options:
+  -p, --part <PART>  only run part 1 or part 2
+  -i, --input <PATH> read input from PATH, a file, a directory of dayN.txt
+                     files or `-` for stdin (default: input/; days with an
+                     empty input are skipped unless picked)
+  -e, --example      read input from example_input/
+  -f, --format <FMT> output as `human` (default), `json` or `csv`
+      --verify       check answers against the known-good ones and exit
+                     with status 1 on a wrong answer
+      --record       store the answers as the known-good ones
+      --answers <DIR> directory of known-good answers (default: answers/)
+  -t, --timeout <SECS> time budget per part, reporting parts that exceed it
+                     as TIMEOUT; also accepts `500ms` or `10s`
+      --visualize <WHERE> `show` drawings of the puzzles after the results,
+                     print them to `stdout` as soon as they are drawn,
+                     write them to dayN-partM.txt files in a directory, or
+                     turn them `off` (default: shown when days are picked
+                     and the format is human, unless VISUALIZE=0)
+  -j, --threads <N>  size of the thread pool, also for bench (default: one
+                     per core; only with the `parallel` feature)
+  -l, --list         list the registered solvers
+  -h, --help         print this help
+
+Bench options:
+      --warmup <N>   untimed runs before measuring (default: 3)
+  -n, --runs <N>     timed runs per part (default: 10)
+      --no-save      do not append the results to the history
+      --history <PATH> benchmark history file (default: bench-history.csv)
+
+Compare options:
+      --baseline <RUN> run to compare against (default: previous)
+      --current <RUN>  run to compare (default: latest)
+                     a run is `latest`, `previous`, a timestamp or a
+                     commit prefix, picking the most recent match
+      --threshold <PERCENT> allowed slowdown of the median (default: 10)
+      --history <PATH> benchmark history file (default: bench-history.csv)";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunOptions {
+    pub selection: Selection,
+    pub input: Source,
+    pub format: Format,
+    pub answers: AnswerMode,
+    pub answer_dir: PathBuf,
+    pub timeout: Option<Duration>,
+    pub threads: Option<usize>,
+    /// Where drawings go, when not left to the defaults.
+    pub visualize: Option<Sink>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+    pub selection: Selection,
+    pub input: Source,
+    pub warmup: usize,
+    pub runs: usize,
+    pub save: bool,
+    pub history: PathBuf,
+    pub threads: Option<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CompareOptions {
+    pub selection: Selection,
+    pub baseline: String,
+    pub current: String,
+    pub threshold: f64,
+    pub history: PathBuf,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    Bench(BenchOptions),
+    Compare(CompareOptions),
+    NewDay(u8),
+    List,
+    Help,
+}
+
+pub fn parse<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+
+    if args.next_if(|arg| arg == "new-day").is_some() {
+        let day = args.next().ok_or("missing day for new-day")?;
+
+        if let Some(arg) = args.next() {
+            return Err(format!("unexpected argument `{}` for new-day", arg));
+        }
+
+        return Ok(Command::NewDay(parse_day(&day)?));
+    }
+
+    let mode = match args.next_if(|arg| arg == "bench" || arg == "compare") {
+        Some(command) if command == "bench" => Mode::Bench,
+        Some(_) => Mode::Compare,
+        None => Mode::Run,
+    };
+
+    let mut days = vec![];
+    let mut part = None;
+    let mut input = None;
+    let mut format = Format::default();
+    let mut answers = AnswerMode::default();
+    let mut answer_dir = PathBuf::from(ANSWER_DIR);
+    let mut timeout = None;
+    let mut threads = None;
+    let mut visualize = None;
+    let mut warmup = bench::WARMUP;
+    let mut runs = bench::RUNS;
+    let mut save = true;
+    let mut history = PathBuf::from(HISTORY_FILE);
+    let mut baseline = "previous".to_string();
+    let mut current = "latest".to_string();
+    let mut threshold = THRESHOLD;
+    let mut list = false;
+
+    while let Some(arg) = args.next() {
+        // Options taking a value may also be written as `--option=value`
+        let (arg, mut inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => {
+                (name.to_string(), Some(value.to_string()))
+            }
+            _ => (arg, None),
+        };
+
+        let mut value = || {
+            inline
+                .take()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("missing value for `{}`", arg))
+        };
+
+        match (arg.as_str(), mode) {
+            ("-h" | "--help", _) => return Ok(Command::Help),
+            ("-l" | "--list", _) => list = true,
+            ("-p" | "--part", _) => part = Some(parse_part(&value()?)?),
+            ("-i" | "--input", Mode::Run | Mode::Bench) => {
+                set_input(&mut input, Source::from_arg(&value()?))?
+            }
+            ("-e" | "--example", Mode::Run | Mode::Bench) => {
+                set_input(&mut input, Source::example())?
+            }
+            ("-f" | "--format", Mode::Run) => format = Format::parse(&value()?)?,
+            ("--verify", Mode::Run) => set_answers(&mut answers, AnswerMode::Verify)?,
+            ("--record", Mode::Run) => set_answers(&mut answers, AnswerMode::Record)?,
+            ("--answers", Mode::Run) => answer_dir = PathBuf::from(value()?),
+            ("-t" | "--timeout", Mode::Run) => timeout = Some(parse_timeout(&value()?)?),
+            ("--visualize", Mode::Run) => visualize = Some(parse_sink(&value()?)),
+            ("-j" | "--threads", Mode::Run | Mode::Bench) if cfg!(feature = "parallel") => {
+                threads = Some(parse_count(&arg, &value()?, 1)?)
+            }
+            ("--warmup", Mode::Bench) => warmup = parse_count(&arg, &value()?, 0)?,
+            ("-n" | "--runs", Mode::Bench) => runs = parse_count(&arg, &value()?, 1)?,
+            ("--no-save", Mode::Bench) => save = false,
+            ("--history", Mode::Bench | Mode::Compare) => history = PathBuf::from(value()?),
+            ("--baseline", Mode::Compare) => baseline = value()?,
+            ("--current", Mode::Compare) => current = value()?,
+            ("--threshold", Mode::Compare) => threshold = parse_threshold(&value()?)?,
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(match mode {
+                    Mode::Run => format!("unknown option `{}`", arg),
+                    Mode::Bench => format!("unknown option `{}` for bench", arg),
+                    Mode::Compare => format!("unknown option `{}` for compare", arg),
+                });
+            }
+            _ => days.append(&mut parse_days(&arg)?),
+        }
+
+        if inline.is_some() {
+            return Err(format!("`{}` does not take a value", arg));
+        }
+    }
+
+    if list {
+        return Ok(Command::List);
+    }
+
+    days.sort();
+    days.dedup();
+
+    let explicit = !days.is_empty() || part.is_some();
+    let input = input.unwrap_or_default();
+
+    if input.is_single() && days.len() != 1 {
+        return Err("reading input from a single file or stdin needs exactly one day".to_string());
+    }
+
+    if days.is_empty() {
+        days = (1..=DAYS).collect();
+    }
+
+    let selection = Selection {
+        days,
+        part,
+        explicit,
+    };
+
+    Ok(match mode {
+        Mode::Run => Command::Run(RunOptions {
+            selection,
+            input,
+            format,
+            answers,
+            answer_dir,
+            timeout,
+            threads,
+            visualize,
+        }),
+        Mode::Bench => Command::Bench(BenchOptions {
+            selection,
+            input,
+            warmup,
+            runs,
+            save,
+            history,
+            threads,
+        }),
+        Mode::Compare => Command::Compare(CompareOptions {
+            selection,
+            baseline,
+            current,
+            threshold,
+            history,
+        }),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Run,
+    Bench,
+    Compare,
+}
+
+fn parse_sink(value: &str) -> Sink {
+    match value {
+        "show" => Sink::Buffer,
+        "stdout" => Sink::Stdout,
+        "off" => Sink::Null,
+        directory => Sink::Files(PathBuf::from(directory)),
+    }
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let seconds = match value.strip_suffix("ms") {
+        Some(millis) => millis.parse::<f64>().map(|millis| millis / 1000.0),
+        None => value.strip_suffix('s').unwrap_or(value).parse::<f64>(),
+    };
+
+    match seconds.map(Duration::try_from_secs_f64) {
+        Ok(Ok(timeout)) if !timeout.is_zero() => Ok(timeout),
+        _ => Err(format!(
+            "invalid timeout `{}`, expected a positive number of seconds",
+            value
+        )),
+    }
+}
+
+fn parse_threshold(value: &str) -> Result<f64, String> {
+    match value.trim_end_matches('%').parse::<f64>() {
+        Ok(threshold) if threshold.is_finite() && threshold >= 0.0 => Ok(threshold),
+        _ => Err(format!(
+            "invalid threshold `{}`, expected a non-negative percentage",
+            value
+        )),
+    }
+}
+
+fn parse_count(arg: &str, value: &str, min: usize) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(count) if count >= min => Ok(count),
+        _ => Err(format!(
+            "invalid value `{}` for `{}`, expected a number of at least {}",
+            value, arg, min
+        )),
+    }
+}
+
+fn set_answers(answers: &mut AnswerMode, mode: AnswerMode) -> Result<(), String> {
+    if *answers != AnswerMode::Off {
+        return Err("`--verify` and `--record` cannot be combined".to_string());
+    }
+
+    *answers = mode;
+
+    Ok(())
+}
+
+fn set_input(input: &mut Option<Source>, source: Source) -> Result<(), String> {
+    if input.is_some() {
+        return Err("`--input` and `--example` can only be given once".to_string());
+    }
+
+    *input = Some(source);
+
+    Ok(())
+}
+
+fn parse_part(value: &str) -> Result<u8, String> {
+    match value.parse::<u8>() {
+        Ok(part) if (1..=PARTS).contains(&part) => Ok(part),
+        _ => Err(format!("invalid part `{}`, expected 1 or 2", value)),
+    }
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    match value.trim().parse::<u8>() {
+        Ok(day) if (1..=DAYS).contains(&day) => Ok(day),
+        _ => Err(format!(
+            "invalid day `{}`, expected a number from 1 to {}",
+            value, DAYS
+        )),
+    }
+}
+
+pub fn parse_days(spec: &str) -> Result<Vec<u8>, String> {
+    let mut days = vec![];
+
+    for item in spec.split(',') {
+        if item == "all" {
+            days.extend(1..=DAYS);
+            continue;
+        }
+
+        match item.split_once('-') {
+            Some((from, to)) => {
+                let from = parse_day(from)?;
+                let to = parse_day(to)?;
+
+                if from > to {
+                    return Err(format!(
+                        "invalid range `{}`, {} is after {}",
+                        item, from, to
+                    ));
+                }
+
+                days.extend(from..=to);
+            }
+            None => days.push(parse_day(item)?),
+        }
+    }
+
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::{parse, parse_days, BenchOptions, Command, CompareOptions, RunOptions};
+    use crate::{
+        answers::AnswerMode, extra::Sink, input::Source, report::Format, solver::Selection,
+    };
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_day_specs() {
+        assert_eq!(parse_days("7"), Ok(vec![7]));
+        assert_eq!(parse_days("3-5"), Ok(vec![3, 4, 5]));
+        assert_eq!(parse_days("1,4,9-10"), Ok(vec![1, 4, 9, 10]));
+        assert_eq!(parse_days("all").map(|days| days.len()), Ok(25));
+        assert!(parse_days("abc").is_err());
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("7-3").is_err());
+        assert!(parse_days("3-").is_err());
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            parse(args(
+                "14 3-4 --part 2 --example --format json --verify --answers expected -t 2.5"
+            )),
+            Ok(Command::Run(RunOptions {
+                selection: Selection {
+                    days: vec![3, 4, 14],
+                    part: Some(2),
+                    explicit: true,
+                },
+                input: Source::example(),
+                format: Format::Json,
+                answers: AnswerMode::Verify,
+                answer_dir: PathBuf::from("expected"),
+                timeout: Some(Duration::from_millis(2500)),
+                threads: None,
+                visualize: None,
+            }))
+        );
+        assert_eq!(parse(args("--list")), Ok(Command::List));
+        assert_eq!(parse(args("1 -h")), Ok(Command::Help));
+        assert!(matches!(
+            parse(args("")),
+            Ok(Command::Run(RunOptions { selection, .. })) if !selection.explicit
+        ));
+        assert!(parse(args("--part 3")).is_err());
+        assert!(parse(args("--part")).is_err());
+        assert!(parse(args("--frobnicate")).is_err());
+        assert!(parse(args("--format xml")).is_err());
+        assert!(parse(args("--verify --record")).is_err());
+        assert!(parse(args("1 --input - --example")).is_err());
+        assert!(parse(args("1-2 --input -")).is_err());
+        assert!(parse(args("--list=yes")).is_err());
+        assert!(parse(args("--timeout 0")).is_err());
+        assert!(parse(args("--timeout soon")).is_err());
+        assert!(parse(args("bench --timeout 1")).is_err());
+        assert!(parse(args("bench --visualize show")).is_err());
+        assert!(matches!(
+            parse(args("--visualize off")),
+            Ok(Command::Run(RunOptions {
+                visualize: Some(Sink::Null),
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse(args("--visualize stdout")),
+            Ok(Command::Run(RunOptions {
+                visualize: Some(Sink::Stdout),
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse(args("--visualize=drawings")),
+            Ok(Command::Run(RunOptions { visualize: Some(Sink::Files(directory)), .. }))
+                if directory.as_os_str() == "drawings"
+        ));
+        assert!(matches!(
+            parse(args("--timeout=250ms")),
+            Ok(Command::Run(RunOptions { timeout, .. })) if timeout == Some(Duration::from_millis(250))
+        ));
+        assert!(matches!(
+            parse(args("--part=2")),
+            Ok(Command::Run(RunOptions { selection, .. })) if selection.part == Some(2)
+        ));
+        assert!(matches!(
+            parse(args("1 --input -")),
+            Ok(Command::Run(RunOptions {
+                input: Source::Stdin,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn parse_bench_command() {
+        assert_eq!(
+            parse(args("bench 16 --runs 5 --warmup=0")),
+            Ok(Command::Bench(BenchOptions {
+                selection: Selection {
+                    days: vec![16],
+                    part: None,
+                    explicit: true,
+                },
+                input: Source::default(),
+                warmup: 0,
+                runs: 5,
+                save: true,
+                history: PathBuf::from("bench-history.csv"),
+                threads: None,
+            }))
+        );
+        assert!(matches!(
+            parse(args("bench --no-save --history old.csv")),
+            Ok(Command::Bench(BenchOptions { save: false, history, .. }))
+                if history.as_os_str() == "old.csv"
+        ));
+        assert!(parse(args("bench --runs 0")).is_err());
+        assert!(parse(args("bench --threads 0")).is_err());
+        assert!(parse(args("compare --threads 2")).is_err());
+        assert_eq!(
+            parse(args("bench -j 1")).is_ok(),
+            cfg!(feature = "parallel")
+        );
+        assert!(parse(args("bench --verify")).is_err());
+        assert!(parse(args("--runs 5")).is_err());
+        assert!(parse(args("--no-save")).is_err());
+    }
+
+    #[test]
+    fn parse_compare_command() {
+        assert_eq!(
+            parse(args("compare 19 --baseline abc123 --threshold=5%")),
+            Ok(Command::Compare(CompareOptions {
+                selection: Selection {
+                    days: vec![19],
+                    part: None,
+                    explicit: true,
+                },
+                baseline: "abc123".to_string(),
+                current: "latest".to_string(),
+                threshold: 5.0,
+                history: PathBuf::from("bench-history.csv"),
+            }))
+        );
+        assert!(parse(args("compare --threshold -1")).is_err());
+        assert!(parse(args("compare --runs 5")).is_err());
+        assert!(parse(args("compare --example")).is_err());
+        assert!(parse(args("--baseline previous")).is_err());
+    }
+
+    #[test]
+    fn parse_new_day_command() {
+        assert_eq!(parse(args("new-day 7")), Ok(Command::NewDay(7)));
+        assert!(parse(args("new-day")).is_err());
+        assert!(parse(args("new-day 26")).is_err());
+        assert!(parse(args("new-day 7 --example")).is_err());
+    }
+}
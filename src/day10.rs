@@ -1,6 +1,13 @@
 use std::fmt::Display;
 
-use crate::extra::visualize;
+use crate::{
+    answers::{Answer, Grid},
+    error::{self, SolveError},
+    extra::visualize,
+    solver::solution,
+};
+
+solution!(10, "Cathode-Ray Tube");
 
 #[derive(Debug, Clone)]
 pub enum Instruction {
@@ -58,27 +65,33 @@ impl Cpu {
     }
 }
 
-impl From<&str> for Instruction {
-    fn from(line: &str) -> Self {
+impl TryFrom<&str> for Instruction {
+    type Error = SolveError;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
         if line.starts_with("noop") {
-            return Self::Noop;
+            return Ok(Self::Noop);
         }
 
         if line.starts_with("addx") {
-            let x_str = line.strip_prefix("addx ").unwrap();
-            let x: i64 = x_str.parse().unwrap();
-            return Self::AddX(x);
+            let x_str = error::strip_prefix(line, "addx ")?;
+            let x: i64 = error::number(x_str)?;
+            return Ok(Self::AddX(x));
         }
 
-        unreachable!()
+        Err(SolveError::new(line, "a `noop` or `addx` instruction"))
     }
 }
 
-pub fn solve_1(input: &str) -> String {
+pub fn parse(input: &str) -> Result<Vec<Instruction>, SolveError> {
+    input.lines().map(Instruction::try_from).collect()
+}
+
+pub fn solve_1(program: &[Instruction]) -> Answer {
     let mut cpu = Cpu {
         cycle: 1,
         register_x: 1,
-        program: input.lines().map(Instruction::from).collect(),
+        program: program.to_vec(),
         ..Default::default()
     };
 
@@ -91,7 +104,7 @@ pub fn solve_1(input: &str) -> String {
         }
     }
 
-    signal_strengths.iter().sum::<i64>().to_string()
+    signal_strengths.iter().sum::<i64>().into()
 }
 
 pub struct Crt {
@@ -125,11 +138,11 @@ impl Display for Crt {
     }
 }
 
-pub fn solve_2(input: &str) -> String {
+pub fn solve_2(program: &[Instruction]) -> Answer {
     let cpu = Cpu {
         cycle: 0,
         register_x: 1,
-        program: input.lines().map(Instruction::from).collect(),
+        program: program.to_vec(),
         ..Default::default()
     };
 
@@ -137,5 +150,5 @@ pub fn solve_2(input: &str) -> String {
 
     visualize(10, 2, &crt);
 
-    format!("{}", crt)
+    Grid::from(crt.to_string().as_str()).into()
 }
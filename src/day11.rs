@@ -1,8 +1,18 @@
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(11, "Monkey in the Middle");
+
+#[derive(Clone)]
 pub enum OperationValue {
     Old,
     Value(usize),
 }
 
+#[derive(Clone)]
 pub enum Operation {
     Add(OperationValue, OperationValue),
     Multiply(OperationValue, OperationValue),
@@ -13,6 +23,7 @@ pub struct Throw {
     pub monkey_index: usize,
 }
 
+#[derive(Clone)]
 pub struct Monkey {
     pub items: Vec<usize>,
     pub operation: Operation,
@@ -22,12 +33,14 @@ pub struct Monkey {
     pub inspections: usize,
 }
 
-impl From<&str> for OperationValue {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for OperationValue {
+    type Error = SolveError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         if value == "old" {
-            OperationValue::Old
+            Ok(OperationValue::Old)
         } else {
-            OperationValue::Value(value.parse().unwrap())
+            Ok(OperationValue::Value(error::number(value)?))
         }
     }
 }
@@ -50,26 +63,28 @@ impl Operation {
     }
 }
 
-impl From<&str> for Operation {
-    fn from(value: &str) -> Self {
-        let operation_str = value.strip_prefix("  Operation: new = ").unwrap();
-        let operation_parts: Vec<&str> = operation_str.splitn(3, ' ').collect();
-        assert!(operation_parts.len() == 3);
+impl TryFrom<&str> for Operation {
+    type Error = SolveError;
 
-        let lhs = OperationValue::from(operation_parts[0]);
-        let op_str = operation_parts[1];
-        let rhs = OperationValue::from(operation_parts[2]);
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (lhs_str, rest) = error::split_once(value, " ", "an operation like `old * 19`")?;
+        let (op_str, rhs_str) = error::split_once(rest, " ", "an operator and operand")?;
+
+        let lhs = OperationValue::try_from(lhs_str)?;
+        let rhs = OperationValue::try_from(rhs_str)?;
 
         match op_str {
-            "+" => Operation::Add(lhs, rhs),
-            "*" => Operation::Multiply(lhs, rhs),
-            _ => unreachable!(),
+            "+" => Ok(Operation::Add(lhs, rhs)),
+            "*" => Ok(Operation::Multiply(lhs, rhs)),
+            _ => Err(SolveError::new(op_str, "`+` or `*`")),
         }
     }
 }
 
-impl From<&str> for Monkey {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for Monkey {
+    type Error = SolveError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let monkey_lines: Vec<&str> = value
             .lines()
             .skip_while(|p| !p.starts_with("Monkey"))
@@ -77,41 +92,34 @@ impl From<&str> for Monkey {
             .take(5)
             .collect();
 
-        let items: Vec<usize> = monkey_lines[0]
-            .strip_prefix("  Starting items: ")
-            .unwrap()
-            .split(", ")
-            .map(|item_str| item_str.parse().unwrap())
-            .collect();
-
-        let operation = Operation::from(monkey_lines[1]);
+        // Missing lines are reported at the end of the monkey
+        let line = |index: usize, prefix: &str| match monkey_lines.get(index) {
+            Some(line) => error::strip_prefix(line, prefix),
+            None => Err(SolveError::new(
+                &value[value.len()..],
+                format!("`{}`", prefix.trim()),
+            )),
+        };
 
-        let test: usize = monkey_lines[2]
-            .strip_prefix("  Test: divisible by ")
-            .unwrap()
-            .parse()
-            .unwrap();
+        let items: Vec<usize> = line(0, "  Starting items: ")?
+            .split(", ")
+            .map(error::number)
+            .collect::<Result<_, _>>()?;
 
-        let test_true: usize = monkey_lines[3]
-            .strip_prefix("    If true: throw to monkey ")
-            .unwrap()
-            .parse()
-            .unwrap();
+        let operation = Operation::try_from(line(1, "  Operation: new = ")?)?;
 
-        let test_false: usize = monkey_lines[4]
-            .strip_prefix("    If false: throw to monkey ")
-            .unwrap()
-            .parse()
-            .unwrap();
+        let test: usize = error::number(line(2, "  Test: divisible by ")?)?;
+        let test_true: usize = error::number(line(3, "    If true: throw to monkey ")?)?;
+        let test_false: usize = error::number(line(4, "    If false: throw to monkey ")?)?;
 
-        Monkey {
+        Ok(Monkey {
             items,
             operation,
             test,
             test_true,
             test_false,
             inspections: 0,
-        }
+        })
     }
 }
 
@@ -128,7 +136,7 @@ impl Monkey {
                 worry /= 3;
             }
 
-            if worry % self.test == 0 {
+            if worry.is_multiple_of(self.test) {
                 thrown.push(Throw {
                     item: worry,
                     monkey_index: self.test_true,
@@ -147,19 +155,16 @@ impl Monkey {
     }
 }
 
-pub fn parse_monkeys(input: &str) -> Vec<Monkey> {
-    let lines: Vec<&str> = input.lines().collect();
-    let monkey_lines = lines.chunks(7);
-
-    let monkey_strings = monkey_lines.map(|lines| lines.join("\n"));
-
-    monkey_strings
-        .map(|monkey_string| Monkey::from(monkey_string.as_str()))
+pub fn parse(input: &str) -> Result<Vec<Monkey>, SolveError> {
+    input
+        .split("\n\n")
+        .filter(|monkey| !monkey.trim().is_empty())
+        .map(Monkey::try_from)
         .collect()
 }
 
-pub fn solve_1(input: &str) -> String {
-    let mut monkeys = parse_monkeys(input);
+pub fn solve_1(monkeys: &[Monkey]) -> Answer {
+    let mut monkeys = monkeys.to_vec();
 
     for _round in 0..20 {
         for monkey_index in 0..monkeys.len() {
@@ -181,11 +186,11 @@ pub fn solve_1(input: &str) -> String {
     inspections.sort();
     inspections.reverse();
 
-    inspections.iter().take(2).product::<usize>().to_string()
+    inspections.iter().take(2).product::<usize>().into()
 }
 
-pub fn solve_2(input: &str) -> String {
-    let mut monkeys = parse_monkeys(input);
+pub fn solve_2(monkeys: &[Monkey]) -> Answer {
+    let mut monkeys = monkeys.to_vec();
 
     let monkey_cycle: usize = monkeys.iter().map(|monkey| monkey.test).product();
 
@@ -209,5 +214,5 @@ pub fn solve_2(input: &str) -> String {
     inspections.sort();
     inspections.reverse();
 
-    inspections.iter().take(2).product::<usize>().to_string()
+    inspections.iter().take(2).product::<usize>().into()
 }
@@ -4,9 +4,11 @@ use std::{
     sync::{Arc, RwLock},
 };
 
-use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use crate::{
+    answers::Answer, error::SolveError, extra::visualize, parallel::prelude::*, solver::solution,
+};
 
-use crate::extra::visualize;
+solution!(12, "Hill Climbing Algorithm");
 
 #[derive(Debug, Clone)]
 pub enum Move {
@@ -79,15 +81,17 @@ pub struct Climb {
     pub route_lengths: HashMap<Point, usize>,
 }
 
-impl From<&str> for Climb {
-    fn from(input: &str) -> Self {
+impl TryFrom<&str> for Climb {
+    type Error = SolveError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
         let mut output = Climb::default();
 
         for (y, line) in input.lines().enumerate() {
             let mut row = vec![];
             output.height = y.max(output.height);
 
-            for (x, char) in line.chars().enumerate() {
+            for (x, (offset, char)) in line.char_indices().enumerate() {
                 output.width = x.max(output.width);
                 let mut elevation = 0;
 
@@ -104,7 +108,12 @@ impl From<&str> for Climb {
                         .enumerate()
                         .find(|(_, reference)| char.eq(reference))
                         .map(|(index, _)| index)
-                        .unwrap();
+                        .ok_or_else(|| {
+                            SolveError::new(
+                                &line[offset..offset + char.len_utf8()],
+                                "an elevation from `a` to `z`, `S` or `E`",
+                            )
+                        })?;
                 }
 
                 row.push(elevation);
@@ -112,7 +121,7 @@ impl From<&str> for Climb {
 
             output.grid.push(row);
         }
-        output
+        Ok(output)
     }
 }
 
@@ -356,8 +365,12 @@ pub fn manage_climbers(climbers: Vec<Climber>, climb: &mut Climb) -> Vec<Climber
     next
 }
 
-pub fn solve_1(input: &str) -> String {
-    let mut climb = Climb::from(input);
+pub fn parse(input: &str) -> Result<Climb, SolveError> {
+    Climb::try_from(input)
+}
+
+pub fn solve_1(climb: &Climb) -> Answer {
+    let mut climb = climb.clone();
     let mut climbers = vec![climb.starting_climber.clone()];
 
     let mut best_climbers: Vec<Climber>;
@@ -376,7 +389,7 @@ pub fn solve_1(input: &str) -> String {
 
     visualize(12, 1, &best_climber);
 
-    best_climber.moves.to_string()
+    best_climber.moves.into()
 }
 
 pub fn best_climber_from_point(
@@ -412,10 +425,9 @@ pub fn best_climber_from_point(
     climbers.first().cloned()
 }
 
-pub fn solve_2(input: &str) -> String {
-    let climb = Climb::from(input);
+pub fn solve_2(climb: &Climb) -> Answer {
     let starting_points = climb.starting_points();
-    let climb = Arc::new(RwLock::new(climb));
+    let climb = Arc::new(RwLock::new(climb.clone()));
 
     let best = usize::MAX;
 
@@ -440,5 +452,5 @@ pub fn solve_2(input: &str) -> String {
 
     visualize(12, 2, &best);
 
-    best.moves.to_string()
+    best.moves.into()
 }
@@ -1,3 +1,11 @@
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(13, "Distress Signal");
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum PacketData {
     Item(usize),
@@ -6,43 +14,50 @@ pub enum PacketData {
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct Packet {
-    data: PacketData,
+    pub data: PacketData,
 }
 
-pub fn parse_packet_data(input: &str) -> (Vec<PacketData>, &str) {
+pub fn parse_packet_data(input: &str) -> Result<(Vec<PacketData>, &str), SolveError> {
     let mut parsed: Vec<PacketData> = vec![];
-    let mut chars = input.chars();
+    let mut item_start = 0;
+    let mut index = 0;
 
-    let mut buffer = String::new();
-
-    while let Some(char) = chars.next() {
-        if char == ']' {
-            if !buffer.is_empty() {
-                parsed.push(PacketData::Item(buffer.parse().unwrap()));
-            }
-            return (parsed, chars.as_str());
-        }
+    while let Some(char) = input[index..].chars().next() {
+        let next = index + char.len_utf8();
 
         if char == '[' {
-            let (nested, rest) = parse_packet_data(chars.as_str());
+            let (nested, rest) = parse_packet_data(&input[next..])?;
             parsed.push(PacketData::List(nested));
-            chars = rest.chars();
+            index = input.len() - rest.len();
+            item_start = index;
             continue;
         }
 
-        if char == ',' {
-            if !buffer.is_empty() {
-                parsed.push(PacketData::Item(buffer.parse().unwrap()));
-                buffer.clear();
+        if char == ']' || char == ',' {
+            let item = &input[item_start..index];
+
+            if !item.is_empty() {
+                parsed.push(PacketData::Item(error::number(item)?));
             }
 
-            continue;
+            if char == ']' {
+                return Ok((parsed, &input[next..]));
+            }
+
+            item_start = next;
         }
 
-        buffer += char.to_string().as_str();
+        index = next;
+    }
+
+    if item_start < index {
+        return Err(SolveError::new(
+            &input[item_start..index],
+            "`[`, `]` or `,`",
+        ));
     }
 
-    (parsed, chars.as_str())
+    Ok((parsed, &input[index..]))
 }
 
 /*
@@ -110,40 +125,47 @@ impl Ord for PacketData {
     }
 }
 
-impl From<&str> for Packet {
-    fn from(input: &str) -> Self {
-        let (packets, _) = parse_packet_data(input);
+impl TryFrom<&str> for Packet {
+    type Error = SolveError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let (packets, _) = parse_packet_data(input)?;
 
-        let top_packet = packets.first().unwrap().clone();
+        let top_packet = packets
+            .first()
+            .ok_or_else(|| SolveError::new(input, "a packet like `[1,[2]]`"))?
+            .clone();
 
-        Self { data: top_packet }
+        Ok(Self { data: top_packet })
     }
 }
 
-pub fn solve_1(input: &str) -> String {
-    let blocks = input.split("\n\n");
+pub fn parse(input: &str) -> Result<Vec<(Packet, Packet)>, SolveError> {
+    input
+        .split("\n\n")
+        .map(|block| match block.lines().collect::<Vec<&str>>()[..] {
+            [left, right] => Ok((Packet::try_from(left)?, Packet::try_from(right)?)),
+            _ => Err(SolveError::new(block, "a pair of packets on two lines")),
+        })
+        .collect()
+}
 
+pub fn solve_1(pairs: &[(Packet, Packet)]) -> Answer {
     let mut sum = 0;
 
-    for (index, block) in blocks.enumerate() {
-        let line_vector: Vec<&str> = block.lines().collect();
-        assert!(line_vector.len() == 2);
-        let left = Packet::from(line_vector[0]);
-        let right = Packet::from(line_vector[1]);
-
+    for (index, (left, right)) in pairs.iter().enumerate() {
         if left < right {
             sum += index + 1;
         }
     }
 
-    sum.to_string()
+    sum.into()
 }
 
-pub fn solve_2(input: &str) -> String {
-    let mut packets: Vec<Packet> = input
-        .replace("\n\n", "\n")
-        .lines()
-        .map(Packet::from)
+pub fn solve_2(pairs: &[(Packet, Packet)]) -> Answer {
+    let mut packets: Vec<Packet> = pairs
+        .iter()
+        .flat_map(|(left, right)| [left.clone(), right.clone()])
         .collect();
 
     let divider_one = Packet {
@@ -171,5 +193,5 @@ pub fn solve_2(input: &str) -> String {
         })
         .product();
 
-    decoder_key.to_string()
+    decoder_key.into()
 }
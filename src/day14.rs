@@ -4,7 +4,15 @@ use std::{
     iter::repeat,
 };
 
-use crate::extra::{visualize, ChristmasGraph, GraphMetadata};
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    extra::{visualize, ChristmasGraph, Color, GraphMetadata, Style},
+    record::Recorder,
+    solver::{solution, Cancel},
+};
+
+solution!(14, "Regolith Reservoir", cancellable);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Cell {
@@ -23,7 +31,6 @@ pub struct Simulation {
     pub y: i64,
     pub width: usize,
     pub height: usize,
-    pub size: usize,
     pub cells: HashMap<(i64, i64), Cell>,
     pub spawn_source: (i64, i64),
     pub resting: usize,
@@ -119,7 +126,6 @@ impl From<&Scan> for Simulation {
             y: 0,
             width,
             height,
-            size: width * height,
             cells,
             spawn_source,
             resting: 0,
@@ -127,18 +133,30 @@ impl From<&Scan> for Simulation {
     }
 }
 
-impl From<&str> for ScanPath {
-    fn from(input: &str) -> Self {
-        let coord_strings = input.split(" -> ");
-        let coords: Vec<(i64, i64)> = coord_strings
-            .filter_map(|coord_str| {
-                coord_str
-                    .split_once(',')
-                    .and_then(|(left, right)| left.parse().ok().zip(right.parse().ok()))
-            })
-            .collect();
-
-        Self { traces: coords }
+impl TryFrom<&str> for ScanPath {
+    type Error = SolveError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let mut coords: Vec<(i64, i64)> = vec![];
+
+        for coord_str in input.split(" -> ") {
+            let (left, right) = error::split_once(coord_str, ",", "a point like `498,4`")?;
+            let coord = (error::number(left)?, error::number(right)?);
+
+            // Paths only run horizontally or vertically
+            if let Some(&(x, y)) = coords.last() {
+                if (x == coord.0) == (y == coord.1) {
+                    return Err(SolveError::new(
+                        coord_str,
+                        "a point in line with the previous one",
+                    ));
+                }
+            }
+
+            coords.push(coord);
+        }
+
+        Ok(Self { traces: coords })
     }
 }
 
@@ -175,6 +193,16 @@ impl ChristmasGraph for Simulation {
             None => None,
         }
     }
+
+    fn graph_style(&self, x: i64, y: i64) -> Style {
+        match self.cells.get(&(x, y)) {
+            Some(Cell::Rock) => Style::color(Color::White).bold(),
+            Some(Cell::Sand(true)) => Style::color(Color::Cyan),
+            Some(Cell::Sand(false)) => Style::color(Color::Yellow),
+            Some(Cell::SandSource) => Style::color(Color::Red).bold(),
+            None => Style::default(),
+        }
+    }
 }
 
 impl Display for Simulation {
@@ -285,11 +313,21 @@ impl Simulation {
         }
     }
 
-    pub fn run(&mut self) {
+    /// Drops sand until it no longer comes to rest, recording the map after
+    /// every grain.
+    pub fn run(&mut self, cancel: &Cancel, recorder: &mut Recorder) {
+        recorder.record(self);
+
         loop {
+            if cancel.is_cancelled() {
+                return;
+            }
+
             if !self.step_resting() {
                 break;
             }
+
+            recorder.record(self);
         }
         let mut step = self.next_position(self.spawn_source);
 
@@ -305,16 +343,20 @@ impl Simulation {
     }
 }
 
-pub fn solve_1(input: &str) -> String {
+pub fn parse(input: &str) -> Result<Scan, SolveError> {
     let mut scan = Scan {
         rocks: HashSet::new(),
     };
 
-    for path in input.lines().map(ScanPath::from) {
-        scan.add_path(path);
+    for line in input.lines() {
+        scan.add_path(ScanPath::try_from(line)?);
     }
 
-    let mut simulation = Simulation::from(&scan);
+    Ok(scan)
+}
+
+pub fn solve_1(scan: &Scan, cancel: &Cancel) -> Answer {
+    let mut simulation = Simulation::from(scan);
 
     simulation.height += 2;
     simulation.width += 2;
@@ -322,23 +364,17 @@ pub fn solve_1(input: &str) -> String {
 
     visualize(14, 1, &simulation);
 
-    simulation.run();
+    let mut recorder = Recorder::new(14, 1);
+    simulation.run(cancel, &mut recorder);
+    recorder.finish(&simulation);
 
     visualize(14, 1, &simulation);
 
-    simulation.resting.to_string()
+    simulation.resting.into()
 }
 
-pub fn solve_2(input: &str) -> String {
-    let mut scan = Scan {
-        rocks: HashSet::new(),
-    };
-
-    for path in input.lines().map(ScanPath::from) {
-        scan.add_path(path);
-    }
-
-    let mut simulation = Simulation::from(&scan);
+pub fn solve_2(scan: &Scan, cancel: &Cancel) -> Answer {
+    let mut simulation = Simulation::from(scan);
 
     let needed_width = (simulation.height + 3) * 2;
 
@@ -352,9 +388,11 @@ pub fn solve_2(input: &str) -> String {
 
     visualize(14, 2, &simulation);
 
-    simulation.run();
+    let mut recorder = Recorder::new(14, 2);
+    simulation.run(cancel, &mut recorder);
+    recorder.finish(&simulation);
 
     visualize(14, 2, &simulation);
 
-    simulation.resting.to_string()
+    simulation.resting.into()
 }
@@ -3,6 +3,19 @@ use std::{
     collections::HashSet,
 };
 
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(15, "Beacon Exclusion Zone", configurable);
+
+/// The row part 1 asks about.
+pub const ROW: isize = 2000000;
+/// The largest coordinate the distress beacon can have in part 2.
+pub const LIMIT: isize = 4000000;
+
 pub struct Sensor {
     pub x: isize,
     pub y: isize,
@@ -33,6 +46,8 @@ pub struct ExclusionZone {
     pub height: usize,
     pub beacons: HashSet<(isize, isize)>,
     pub sensors: Vec<Sensor>,
+    pub row: isize,
+    pub limit: isize,
 }
 
 impl ExclusionZone {
@@ -48,9 +63,7 @@ impl ExclusionZone {
                 .sensors
                 .iter()
                 .filter_map(|sensor| {
-                    let Some(mut reachable_range) = sensor.x_range(at_y) else {
-                        return None
-                    };
+                    let mut reachable_range = sensor.x_range(at_y)?;
 
                     reachable_range.0 = reachable_range.0.clamp(from_x, to_x);
                     reachable_range.1 = reachable_range.1.clamp(from_x, to_x);
@@ -64,7 +77,7 @@ impl ExclusionZone {
                 let (x1, x2) = ranges[0];
                 let (x3, x4) = ranges[1];
 
-                let mut values = vec![x1, x2, x3, x4];
+                let mut values = [x1, x2, x3, x4];
                 values.sort();
 
                 Some(((values[1] + 1), at_y))
@@ -111,26 +124,35 @@ impl ExclusionZone {
     }
 }
 
-impl From<&str> for ExclusionZone {
-    fn from(value: &str) -> Self {
-        let mut output = Self::default();
-        let mut max_x = 0;
-        let mut max_y = 0;
+fn parse_position(text: &str) -> Result<(isize, isize), SolveError> {
+    let (x_str, y_str) = error::split_once(text, ", ", "a position like `x=2, y=18`")?;
 
-        for line in value.lines() {
-            let (mut sensor_str, mut beacon_str) = line.split_once(": ").unwrap();
-            sensor_str = sensor_str.strip_prefix("Sensor at ").unwrap();
-            let (sensor_x_str, sensor_y_str) = sensor_str.split_once(", ").unwrap();
+    Ok((
+        error::number(error::strip_prefix(x_str, "x=")?)?,
+        error::number(error::strip_prefix(y_str, "y=")?)?,
+    ))
+}
 
-            let sensor_x: isize = sensor_x_str.strip_prefix("x=").unwrap().parse().unwrap();
-            let sensor_y: isize = sensor_y_str.strip_prefix("y=").unwrap().parse().unwrap();
+impl TryFrom<&str> for ExclusionZone {
+    type Error = SolveError;
 
-            beacon_str = beacon_str.strip_prefix("closest beacon is at ").unwrap();
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut output = Self {
+            row: ROW,
+            limit: LIMIT,
+            ..Self::default()
+        };
+        let mut max_x = 0;
+        let mut max_y = 0;
 
-            let (beacon_x_str, beacon_y_str) = beacon_str.split_once(", ").unwrap();
+        for line in value.lines() {
+            let (sensor_str, beacon_str) =
+                error::split_once(line, ": ", "a sensor and its closest beacon")?;
 
-            let beacon_x: isize = beacon_x_str.strip_prefix("x=").unwrap().parse().unwrap();
-            let beacon_y: isize = beacon_y_str.strip_prefix("y=").unwrap().parse().unwrap();
+            let (sensor_x, sensor_y) =
+                parse_position(error::strip_prefix(sensor_str, "Sensor at ")?)?;
+            let (beacon_x, beacon_y) =
+                parse_position(error::strip_prefix(beacon_str, "closest beacon is at ")?)?;
 
             let manhattan_distance =
                 ((sensor_x - beacon_x).abs() + (sensor_y - beacon_y).abs()) as usize;
@@ -157,7 +179,7 @@ impl From<&str> for ExclusionZone {
         output.width = width;
         output.height = height;
 
-        output
+        Ok(output)
     }
 }
 
@@ -180,25 +202,40 @@ fn merge_ranges(ranges: Vec<(isize, isize)>) -> Vec<(isize, isize)> {
     merged_ranges
 }
 
-pub fn solve_1(input: &str) -> String {
-    let zone = ExclusionZone::from(input);
+pub fn parse(input: &str) -> Result<ExclusionZone, SolveError> {
+    ExclusionZone::try_from(input)
+}
 
-    if cfg!(test) {
-        zone.exclusion_count(10).to_string()
-    } else {
-        zone.exclusion_count(2000000).to_string()
+/// The examples ask about a smaller area, with `row` 10 and `limit` 20.
+pub fn configure(zone: &mut ExclusionZone, name: &str, value: &str) -> Result<(), String> {
+    let number = value.parse().map_err(|_| {
+        format!(
+            "invalid value `{}` for `{}`, expected a number",
+            value, name
+        )
+    })?;
+
+    match name {
+        "row" => zone.row = number,
+        "limit" => zone.limit = number,
+        _ => return Err(format!("day 15 has no parameter `{}`", name)),
     }
+
+    Ok(())
+}
+
+pub fn solve_1(zone: &ExclusionZone) -> Answer {
+    zone.exclusion_count(zone.row).into()
 }
-pub fn solve_2(input: &str) -> String {
-    let zone = ExclusionZone::from(input);
 
+pub fn solve_2(zone: &ExclusionZone) -> Answer {
     let beacon = zone
-        .find_beacon(0, 0, 4000000, 4000000)
+        .find_beacon(0, 0, zone.limit, zone.limit)
         .expect("find beacon");
 
     let tuning_frequency = beacon.0 * 4000000 + beacon.1;
 
-    tuning_frequency.to_string()
+    tuning_frequency.into()
 }
 
 #[test]
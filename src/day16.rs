@@ -1,7 +1,13 @@
-use std::collections::HashMap;
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::{solution, Cancel},
+};
+
+solution!(16, "Proboscidea Volcanium", cancellable);
 
 #[derive(Clone)]
-struct ValveSystem {
+pub struct ValveSystem {
     size: u8,
     names: Vec<String>,
     flow_rates: Vec<u8>,
@@ -75,26 +81,10 @@ struct State {
     pressure: u16,
 }
 
-#[derive(PartialEq, Eq)]
-struct BoundedState {
-    state: State,
-    bound: u16,
-}
-
-impl Ord for BoundedState {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.bound.cmp(&other.bound)
-    }
-}
+impl TryFrom<&str> for ValveSystem {
+    type Error = SolveError;
 
-impl PartialOrd for BoundedState {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl From<&str> for ValveSystem {
-    fn from(value: &str) -> Self {
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let mut output = Self {
             size: 0,
             names: vec![],
@@ -104,45 +94,38 @@ impl From<&str> for ValveSystem {
         };
 
         let mut neighbors = vec![];
+        let mut valve_names = vec![];
 
-        value.lines().for_each(|line| {
-            let line = line.strip_prefix("Valve ").unwrap();
-            let (valve_name, line) = line.split_once(' ').unwrap();
-            let (flow_rate_str, line) = line
-                .strip_prefix("has flow rate=")
-                .unwrap()
-                .split_once(';')
-                .unwrap();
-            let (_, line) = line.split_once("valve").unwrap().1.split_once(' ').unwrap();
-
-            let mut connections: Vec<String> =
-                line.split(", ").map(|str| str.to_string()).collect();
-            if connections.is_empty() {
-                connections = vec![line.to_string()];
-            }
-            let valve_name = valve_name.to_string();
+        for line in value.lines() {
+            let line = error::strip_prefix(line, "Valve ")?;
+            let (valve_name, line) = error::split_once(line, " ", "a valve name")?;
+            let (flow_rate_str, line) = error::split_once(
+                error::strip_prefix(line, "has flow rate=")?,
+                ";",
+                "a flow rate followed by `;`",
+            )?;
+            let (_, line) = error::split_once(line, "valve", "the tunnels to other valves")?;
+            let (_, line) = error::split_once(line, " ", "a list of valves")?;
 
-            let flow_rate = flow_rate_str.parse().unwrap();
+            let connections: Vec<&str> = line.split(", ").collect();
 
-            output.names.push(valve_name);
+            let flow_rate = error::number(flow_rate_str)?;
+
+            valve_names.push(valve_name);
+            output.names.push(valve_name.to_string());
             output.flow_rates.push(flow_rate);
             output.size += 1;
 
             neighbors.push(connections);
-        });
+        }
 
         let mut distances = vec![vec![None; output.size as usize]; output.size as usize];
 
-        for (index, neighbors) in neighbors.iter().cloned().enumerate() {
+        for (index, neighbors) in neighbors.iter().enumerate() {
             for neighbor in neighbors {
-                let Some((valve_id, _)) = output
-                    .names
-                    .clone()
-                    .into_iter()
-                    .enumerate()
-                    .find(|(_, name)| neighbor.eq(name)) else {
-                        continue;
-                    };
+                let Some(valve_id) = output.names.iter().position(|name| neighbor.eq(name)) else {
+                    return Err(SolveError::new(neighbor, "the name of a listed valve"));
+                };
 
                 distances[index][valve_id] = Some(1);
             }
@@ -152,15 +135,26 @@ impl From<&str> for ValveSystem {
             distances[identity][identity] = Some(0);
         });
 
+        if !valve_names.contains(&"AA") {
+            return Err(SolveError::new(&value[value.len()..], "a valve named `AA`"));
+        }
+
         let distances = floyd_warshall(&distances);
         let distances: Vec<Vec<u8>> = distances
             .iter()
-            .map(|row| {
+            .enumerate()
+            .map(|(from, row)| {
                 row.iter()
-                    .map(|dist| dist.expect("distance to all nodes known"))
+                    .enumerate()
+                    .map(|(to, dist)| {
+                        dist.ok_or_else(|| {
+                            let expected = format!("a valve reachable from {}", valve_names[from]);
+                            SolveError::new(valve_names[to], expected)
+                        })
+                    })
                     .collect()
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
         output.distances = distances;
 
@@ -170,12 +164,12 @@ impl From<&str> for ValveSystem {
         output.flow_rates_sorted.sort();
         output.flow_rates_sorted.reverse();
 
-        output
+        Ok(output)
     }
 }
-fn floyd_warshall(graph: &Vec<Vec<Option<u8>>>) -> Vec<Vec<Option<u8>>> {
+fn floyd_warshall(graph: &[Vec<Option<u8>>]) -> Vec<Vec<Option<u8>>> {
     let n = graph.len();
-    let mut distance = graph.clone();
+    let mut distance = graph.to_owned();
 
     for k in 0..n {
         for i in 0..n {
@@ -240,9 +234,13 @@ impl State {
                 .sum::<u16>()
     }
 
-    fn branch_and_bound(&self, system: &ValveSystem, pressure: &mut u16) {
+    fn branch_and_bound(&self, system: &ValveSystem, pressure: &mut u16, cancel: &Cancel) {
         *pressure = self.pressure.max(*pressure);
 
+        if cancel.is_cancelled() {
+            return;
+        }
+
         for branch in self.branch(system) {
             let upper_bound = branch.bound(system);
 
@@ -250,14 +248,16 @@ impl State {
                 continue;
             }
 
-            branch.branch_and_bound(system, pressure);
+            branch.branch_and_bound(system, pressure, cancel);
         }
     }
 }
 
-pub fn solve_1(input: &str) -> String {
-    let system = ValveSystem::from(input);
+pub fn parse(input: &str) -> Result<ValveSystem, SolveError> {
+    ValveSystem::try_from(input)
+}
 
+pub fn solve_1(system: &ValveSystem, cancel: &Cancel) -> Answer {
     let state = State {
         position: system.start(),
         pressure: 0,
@@ -267,20 +267,22 @@ pub fn solve_1(input: &str) -> String {
 
     let mut pressure = 0;
 
-    state.branch_and_bound(&system, &mut pressure);
+    state.branch_and_bound(system, &mut pressure, cancel);
 
-    pressure.to_string()
+    pressure.into()
 }
-pub fn solve_2(input: &str) -> String {
-    let system = ValveSystem::from(input);
-
+pub fn solve_2(system: &ValveSystem, cancel: &Cancel) -> Answer {
     let mask = (1u64 << system.size) - 1;
 
-    let halfway = (mask + 1) / 2;
+    let halfway = mask.div_ceil(2);
 
     let mut best = 0;
 
     for index in 0..halfway {
+        if cancel.is_cancelled() {
+            break;
+        }
+
         let visited = index;
         let elephant_visited = index ^ mask;
 
@@ -300,14 +302,14 @@ pub fn solve_2(input: &str) -> String {
 
         let mut pressure = 0;
 
-        state.branch_and_bound(&system, &mut pressure);
+        state.branch_and_bound(system, &mut pressure, cancel);
 
         let mut elephant_pressure = 0;
 
-        elephant.branch_and_bound(&system, &mut elephant_pressure);
+        elephant.branch_and_bound(system, &mut elephant_pressure, cancel);
 
         best = best.max(pressure + elephant_pressure)
     }
 
-    best.to_string()
+    best.into()
 }
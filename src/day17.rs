@@ -1,5 +1,18 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::{
+    answers::Answer,
+    error::SolveError,
+    extra::{ChristmasGraph, Color, GraphMetadata, Style},
+    record::Recorder,
+    solver::{solution, Cancel},
+};
+
+solution!(17, "Pyroclastic Flow", cancellable);
+
+/// The rows of the chamber below the spawn point that are drawn.
+const CHAMBER_ROWS: u64 = 40;
+
 #[derive(Clone)]
 pub struct Shape {
     parts: Vec<(u64, u64)>,
@@ -132,6 +145,67 @@ impl Shape {
     }
 }
 
+/// The top of the chamber, with the rock still falling, if any. Heights grow
+/// upwards, so rows are drawn at their negated height.
+pub struct Chamber<'a> {
+    rocks: &'a HashSet<(u64, u64)>,
+    falling: Option<&'a Shape>,
+    top: u64,
+}
+
+impl Chamber<'_> {
+    fn highest(&self) -> u64 {
+        let falling = self.falling.map_or(0, |shape| shape.max_y);
+
+        falling.max(self.top + 7)
+    }
+}
+
+impl ChristmasGraph for Chamber<'_> {
+    fn as_graph_metadata(&self) -> GraphMetadata {
+        let highest = self.highest();
+
+        GraphMetadata {
+            x: 0,
+            y: -(highest as i64),
+            width: 7,
+            height: (highest + 1).min(CHAMBER_ROWS) as u32,
+            legend_step_x: 1,
+            legend_step_y: 5,
+            legend_y_width: highest.to_string().len() as u32,
+            legend_x_width: 1,
+        }
+    }
+
+    fn graph_legend_x(&self, value: i64) -> Vec<char> {
+        value.to_string().chars().collect()
+    }
+
+    fn graph_legend_y(&self, value: i64) -> Vec<char> {
+        (-value).to_string().chars().collect()
+    }
+
+    fn graph_value(&self, x: i64, y: i64) -> Option<char> {
+        let cell = (u64::try_from(x).ok()?, u64::try_from(-y).ok()?);
+
+        if self
+            .falling
+            .is_some_and(|shape| shape.parts.contains(&cell))
+        {
+            Some('@')
+        } else {
+            self.rocks.contains(&cell).then_some('#')
+        }
+    }
+
+    fn graph_style(&self, x: i64, y: i64) -> Style {
+        match self.graph_value(x, y) {
+            Some('@') => Style::color(Color::Yellow).bold(),
+            _ => Style::color(Color::White),
+        }
+    }
+}
+
 fn fingerprint<'a, R>(rocks: R, top: u64, depth: u64) -> [u64; 7]
 where
     R: IntoIterator<Item = &'a (u64, u64)>,
@@ -152,9 +226,10 @@ where
     fingerprint
 }
 
-pub fn solve(input: &str, total_shapes: u64) -> u64 {
-    let chars: Vec<char> = input.chars().collect();
-    let jets = input.len() as u64;
+/// The height of the tower after `total_shapes` rocks, recording the falling
+/// rocks after every move.
+pub fn solve(chars: &[char], total_shapes: u64, cancel: &Cancel, mut recorder: Recorder) -> u64 {
+    let jets = chars.len() as u64;
     let mut shape_counter = 0u64;
     let mut jet_counter = 0u64;
     let mut top = 0;
@@ -177,6 +252,10 @@ pub fn solve(input: &str, total_shapes: u64) -> u64 {
     let mut cache = HashMap::<Key, (u64, u64)>::new();
 
     while shape_counter < total_shapes {
+        if cancel.is_cancelled() {
+            break;
+        }
+
         let shape_index = (shape_counter % 5) as usize;
         shape_counter += 1;
 
@@ -216,6 +295,12 @@ pub fn solve(input: &str, total_shapes: u64) -> u64 {
 
                 top = top.max(shape.max_y);
 
+                recorder.record(&Chamber {
+                    rocks: &rocks_set,
+                    falling: None,
+                    top,
+                });
+
                 let key: Key = (
                     jet_index,
                     shape_index,
@@ -237,16 +322,43 @@ pub fn solve(input: &str, total_shapes: u64) -> u64 {
 
                 break;
             }
+
+            recorder.record(&Chamber {
+                rocks: &rocks_set,
+                falling: Some(&shape),
+                top,
+            });
         }
     }
 
+    recorder.finish(&Chamber {
+        rocks: &rocks_set,
+        falling: None,
+        top,
+    });
+
     top + added
 }
 
-pub fn solve_1(input: &str) -> String {
-    solve(input, 2022).to_string()
+pub fn parse(input: &str) -> Result<Vec<char>, SolveError> {
+    let jets = input.trim_end();
+
+    match jets
+        .char_indices()
+        .find(|(_, jet)| *jet != '<' && *jet != '>')
+    {
+        Some((index, jet)) => Err(SolveError::new(
+            &jets[index..index + jet.len_utf8()],
+            "a jet, `<` or `>`",
+        )),
+        None => Ok(jets.chars().collect()),
+    }
+}
+
+pub fn solve_1(jets: &[char], cancel: &Cancel) -> Answer {
+    solve(jets, 2022, cancel, Recorder::new(17, 1)).into()
 }
 
-pub fn solve_2(input: &str) -> String {
-    solve(input, 1000000000000).to_string()
+pub fn solve_2(jets: &[char], cancel: &Cancel) -> Answer {
+    solve(jets, 1000000000000, cancel, Recorder::new(17, 2)).into()
 }
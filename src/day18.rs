@@ -1,3 +1,11 @@
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(18, "Boiling Boulders");
+
 #[derive(Clone, PartialEq)]
 pub struct Position {
     x: u64,
@@ -56,26 +64,25 @@ impl Position {
     }
 }
 
-impl From<&str> for Position {
-    fn from(value: &str) -> Self {
-        let parts: Vec<&str> = value.splitn(3, ',').collect();
+impl TryFrom<&str> for Position {
+    type Error = SolveError;
 
-        Self {
-            x: parts[0].parse().unwrap(),
-            y: parts[1].parse().unwrap(),
-            z: parts[2].parse().unwrap(),
-        }
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (x, rest) = error::split_once(value, ",", "a cube like `2,2,2`")?;
+        let (y, z) = error::split_once(rest, ",", "a cube like `2,2,2`")?;
+
+        Ok(Self {
+            x: error::number(x)?,
+            y: error::number(y)?,
+            z: error::number(z)?,
+        })
     }
 }
 
 pub fn count_connected_sides(mut cubes: Vec<Position>) -> usize {
     let mut count = 0;
 
-    loop {
-        let Some(current) = cubes.pop() else {
-            break;
-        };
-
+    while let Some(current) = cubes.pop() {
         for other in &cubes {
             if current.connects(other) {
                 count += 1;
@@ -156,17 +163,20 @@ pub fn find_trapped_air(cubes: &Vec<Position>) -> Vec<Position> {
     trapped
 }
 
-pub fn solve_1(input: &str) -> String {
-    let cubes: Vec<Position> = input.lines().map(Position::from).collect();
+pub fn parse(input: &str) -> Result<Vec<Position>, SolveError> {
+    input.lines().map(Position::try_from).collect()
+}
+
+pub fn solve_1(cubes: &[Position]) -> Answer {
     let sides = cubes.len() * 6;
 
-    (sides - count_connected_sides(cubes) * 2).to_string()
+    (sides - count_connected_sides(cubes.to_vec()) * 2).into()
 }
-pub fn solve_2(input: &str) -> String {
-    let mut cubes: Vec<Position> = input.lines().map(Position::from).collect();
+pub fn solve_2(cubes: &[Position]) -> Answer {
+    let mut cubes = cubes.to_vec();
     let mut trapped_air = find_trapped_air(&cubes);
     cubes.append(&mut trapped_air);
 
     let sides = cubes.len() * 6;
-    (sides - count_connected_sides(cubes) * 2).to_string()
+    (sides - count_connected_sides(cubes) * 2).into()
 }
@@ -3,7 +3,14 @@ use std::{
     ops::{AddAssign, SubAssign},
 };
 
-use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    parallel::prelude::*,
+    solver::{solution, Cancel},
+};
+
+solution!(19, "Not Enough Minerals", cancellable);
 
 const ORE: Inventory = Inventory {
     ore: 1,
@@ -108,7 +115,7 @@ impl Inventory {
     }
 }
 
-struct Blueprint {
+pub struct Blueprint {
     id: u8,
     ore: Inventory,
     clay: Inventory,
@@ -178,7 +185,7 @@ impl State {
             time -= 1;
 
             if obsidian >= geode_cost.obsidian {
-                geodes = geodes.checked_add(time).unwrap_or(u8::MAX);
+                geodes = geodes.saturating_add(time);
                 obsidian -= geode_cost.obsidian;
                 obsidian += obsidian_robots;
                 continue;
@@ -258,6 +265,7 @@ impl State {
         priority_queue: &mut BinaryHeap<BranchState>,
         time: u8,
         max: &mut u8,
+        cancel: &Cancel,
     ) -> u8 {
         if time == 0 {
             *max = (*max).max(self.resources.geode);
@@ -275,11 +283,15 @@ impl State {
         }
 
         while let Some(BranchState { state, bound, time }) = priority_queue.pop() {
+            if cancel.is_cancelled() {
+                break;
+            }
+
             if bound <= *max {
                 continue;
             }
 
-            let geodes = state.branch_and_bound(blueprint, priority_queue, time - 1, max);
+            let geodes = state.branch_and_bound(blueprint, priority_queue, time - 1, max, cancel);
 
             *max = (*max).max(geodes);
         }
@@ -312,16 +324,26 @@ impl State {
     } */
 }
 
-impl From<&str> for Blueprint {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for Blueprint {
+    type Error = SolveError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let parsed: Vec<u8> = value
-            .replace(':', " ")
             .split_whitespace()
-            .filter_map(|word| word.parse::<u8>().ok())
+            .map(|word| word.trim_end_matches(':'))
+            .filter(|word| word.starts_with(|char: char| char.is_ascii_digit()))
             .take(7)
-            .collect();
+            .map(error::number)
+            .collect::<Result<_, _>>()?;
+
+        if parsed.len() != 7 {
+            return Err(SolveError::new(
+                value,
+                "a blueprint with an id and six costs",
+            ));
+        }
 
-        Self {
+        Ok(Self {
             id: parsed[0],
             ore: Inventory {
                 ore: parsed[1],
@@ -341,13 +363,15 @@ impl From<&str> for Blueprint {
                 obsidian: parsed[6],
                 ..Default::default()
             },
-        }
+        })
     }
 }
 
-pub fn solve_1(input: &str) -> String {
-    let blueprints: Vec<Blueprint> = input.lines().map(Blueprint::from).collect();
+pub fn parse(input: &str) -> Result<Vec<Blueprint>, SolveError> {
+    input.lines().map(Blueprint::try_from).collect()
+}
 
+pub fn solve_1(blueprints: &[Blueprint], cancel: &Cancel) -> Answer {
     let result: u32 = blueprints
         .par_iter()
         .map(|blueprint| {
@@ -358,17 +382,21 @@ pub fn solve_1(input: &str) -> String {
             };
 
             let mut max_geodes = 0;
-            state.branch_and_bound(blueprint, &mut BinaryHeap::new(), 24, &mut max_geodes);
+            state.branch_and_bound(
+                blueprint,
+                &mut BinaryHeap::new(),
+                24,
+                &mut max_geodes,
+                cancel,
+            );
 
             max_geodes as u32 * blueprint.id as u32
         })
         .sum();
 
-    result.to_string()
+    result.into()
 }
-pub fn solve_2(input: &str) -> String {
-    let blueprints: Vec<Blueprint> = input.lines().map(Blueprint::from).collect();
-
+pub fn solve_2(blueprints: &[Blueprint], cancel: &Cancel) -> Answer {
     let result: u32 = blueprints
         .par_iter()
         .take(3)
@@ -380,11 +408,17 @@ pub fn solve_2(input: &str) -> String {
             };
 
             let mut max_geodes = 0;
-            state.branch_and_bound(blueprint, &mut BinaryHeap::new(), 32, &mut max_geodes);
+            state.branch_and_bound(
+                blueprint,
+                &mut BinaryHeap::new(),
+                32,
+                &mut max_geodes,
+                cancel,
+            );
 
             max_geodes as u32
         })
         .product();
 
-    result.to_string()
+    result.into()
 }
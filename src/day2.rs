@@ -1,3 +1,11 @@
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(2, "Rock Paper Scissors");
+
 #[derive(Clone)]
 pub enum Kind {
     Rock,
@@ -11,31 +19,35 @@ pub struct Match {
     pub player_two: Kind,
 }
 
-impl From<&str> for Kind {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for Kind {
+    type Error = SolveError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "A" => Kind::Rock,
-            "B" => Kind::Paper,
-            "C" => Kind::Scissors,
-            "X" => Kind::Rock,
-            "Y" => Kind::Paper,
-            "Z" => Kind::Scissors,
-            _ => unreachable!(),
+            "A" => Ok(Kind::Rock),
+            "B" => Ok(Kind::Paper),
+            "C" => Ok(Kind::Scissors),
+            "X" => Ok(Kind::Rock),
+            "Y" => Ok(Kind::Paper),
+            "Z" => Ok(Kind::Scissors),
+            _ => Err(SolveError::new(
+                value,
+                "one of `A`, `B`, `C`, `X`, `Y` or `Z`",
+            )),
         }
     }
 }
 
-impl From<&str> for Match {
-    fn from(line: &str) -> Self {
-        let pair = line
-            .split_once(' ')
-            .map(|(a_str, b_str)| (Kind::from(a_str), Kind::from(b_str)))
-            .expect("split line");
+impl TryFrom<&str> for Match {
+    type Error = SolveError;
 
-        Self {
-            player_one: pair.1,
-            player_two: pair.0,
-        }
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let (a_str, b_str) = error::split_once(line, " ", "two moves separated by a space")?;
+
+        Ok(Self {
+            player_one: Kind::try_from(b_str)?,
+            player_two: Kind::try_from(a_str)?,
+        })
     }
 }
 
@@ -55,13 +67,12 @@ impl Match {
     }
 }
 
-pub fn solve_1(input: &str) -> String {
-    input
-        .lines()
-        .map(Match::from)
-        .map(|m| m.score())
-        .sum::<u64>()
-        .to_string()
+pub fn parse(input: &str) -> Result<Vec<Match>, SolveError> {
+    input.lines().map(Match::try_from).collect()
+}
+
+pub fn solve_1(matches: &[Match]) -> Answer {
+    matches.iter().map(|m| m.score()).sum::<u64>().into()
 }
 
 pub enum Outcome {
@@ -94,10 +105,10 @@ impl Kind {
     }
 }
 
-pub fn solve_2(input: &str) -> String {
-    input
-        .lines()
-        .map(Match::from)
+pub fn solve_2(matches: &[Match]) -> Answer {
+    matches
+        .iter()
+        .cloned()
         .map(|mut m| {
             let instruction = Outcome::from(&m.player_one);
             let new_move = m.player_two.move_with_outcome(instruction);
@@ -105,5 +116,5 @@ pub fn solve_2(input: &str) -> String {
             m.score()
         })
         .sum::<u64>()
-        .to_string()
+        .into()
 }
@@ -1,3 +1,11 @@
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(20, "Grove Positioning System");
+
 #[derive(Clone, Debug)]
 struct SequenceNumber {
     identifier: i64,
@@ -50,7 +58,7 @@ impl State {
             .0
             .iter()
             .enumerate()
-            .find(|(i, number)| number.value == 0)
+            .find(|(_, number)| number.value == 0)
             .unwrap()
             .0 as i64;
 
@@ -62,11 +70,15 @@ impl State {
     }
 }
 
-pub fn solve_1(input: &str) -> String {
+pub fn parse(input: &str) -> Result<Vec<i64>, SolveError> {
+    input.lines().map(error::number).collect()
+}
+
+pub fn solve_1(numbers: &[i64]) -> Answer {
     let mut state = State(
-        input
-            .lines()
-            .map(|line| line.parse::<i64>().unwrap())
+        numbers
+            .iter()
+            .copied()
             .enumerate()
             .map(|(id, value)| SequenceNumber {
                 identifier: id as i64,
@@ -77,13 +89,13 @@ pub fn solve_1(input: &str) -> String {
 
     state.mix();
 
-    state.as_grove_coordinate().to_string()
+    state.as_grove_coordinate().into()
 }
-pub fn solve_2(input: &str) -> String {
+pub fn solve_2(numbers: &[i64]) -> Answer {
     let mut state = State(
-        input
-            .lines()
-            .map(|line| line.parse::<i64>().unwrap())
+        numbers
+            .iter()
+            .copied()
             .enumerate()
             .map(|(id, value)| SequenceNumber {
                 identifier: id as i64,
@@ -94,5 +106,5 @@ pub fn solve_2(input: &str) -> String {
 
     (0..10).for_each(|_| state.mix());
 
-    state.as_grove_coordinate().to_string()
+    state.as_grove_coordinate().into()
 }
@@ -1,5 +1,16 @@
-use core::panic;
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(21, "Monkey Math");
 
 type JobRef = Rc<RefCell<Job>>;
 
@@ -30,56 +41,58 @@ enum SymbolicJob {
 }
 
 #[derive(Debug)]
-struct Monkey {
+pub struct Monkey {
     name: String,
     symbolic_job: SymbolicJob,
 }
 
-impl From<&str> for Monkey {
-    fn from(value: &str) -> Self {
-        let (name, value) = value.split_once(':').unwrap();
+impl TryFrom<&str> for Monkey {
+    type Error = SolveError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (name, value) = error::split_once(value, ":", "a monkey name followed by `:`")?;
         let value = value.trim();
 
-        let values: Vec<String> = value
-            .split_whitespace()
-            .map(|str| str.to_string())
-            .collect();
+        let values: Vec<&str> = value.split_whitespace().collect();
 
         if values.len() != 3 {
-            return Monkey {
+            return Ok(Monkey {
                 name: name.to_string(),
                 symbolic_job: if name != "humn" {
-                    SymbolicJob::Yell(value.parse().unwrap())
+                    SymbolicJob::Yell(error::number(value)?)
                 } else {
-                    SymbolicJob::Human(value.parse().unwrap())
+                    SymbolicJob::Human(error::number(value)?)
                 },
-            };
+            });
         }
 
         let lhs = values[0].to_string();
-        let op = values[1].to_string();
+        let op = values[1];
         let rhs = values[2].to_string();
 
-        let job = match op.as_str() {
+        let job = match op {
             "+" => SymbolicJob::Add(lhs, rhs),
             "-" => SymbolicJob::Sub(lhs, rhs),
             "/" => SymbolicJob::Div(lhs, rhs),
             "*" => SymbolicJob::Mul(lhs, rhs),
-            _ => panic!("bad operand {}", op),
+            _ => return Err(SolveError::new(op, "one of `+`, `-`, `*` or `/`")),
         };
 
-        Monkey {
+        Ok(Monkey {
             name: name.to_string(),
             symbolic_job: job,
-        }
+        })
     }
 }
 
 fn grab_monkey<'a>(name: &str, monkeys: &'a [Monkey]) -> &'a Monkey {
-    monkeys.iter().find(|monkey| monkey.name.eq(name)).unwrap()
+    monkeys
+        .iter()
+        .find(|monkey| monkey.name.eq(name))
+        .expect("names checked while parsing")
 }
 
-fn make_job(name: &String, monkeys: &Vec<Monkey>, jobs: &mut HashMap<String, JobRef>) -> JobRef {
+fn make_job(name: &String, monkeys: &[Monkey], jobs: &mut HashMap<String, JobRef>) -> JobRef {
     if let Some(cached) = jobs.get(name) {
         return cached.clone();
     }
@@ -314,12 +327,83 @@ impl RootJob {
     }
 }
 
-pub fn solve_1(input: &str) -> String {
-    let monkeys: Vec<Monkey> = input.lines().map(Monkey::from).collect();
+pub fn parse(input: &str) -> Result<Vec<Monkey>, SolveError> {
+    let monkeys: Vec<Monkey> = input
+        .lines()
+        .map(Monkey::try_from)
+        .collect::<Result<_, _>>()?;
+
+    // The jobs are looked up again in the input to point at the names
+    let mut jobs = vec![];
+    let mut names = HashSet::new();
+
+    for line in input.lines() {
+        let (name, job) = error::split_once(line, ":", "a monkey name followed by `:`")?;
+
+        if !names.insert(name) {
+            return Err(SolveError::new(name, "the name of a new monkey"));
+        }
+
+        match job.split_whitespace().collect::<Vec<_>>()[..] {
+            [lhs, _, rhs] if name != "root" => jobs.push((name, [lhs, rhs])),
+            [lhs, "+", rhs] => jobs.push((name, [lhs, rhs])),
+            _ if name == "root" => {
+                return Err(SolveError::new(job.trim(), "two monkeys added together"));
+            }
+            _ => {}
+        }
+    }
+
+    if !names.contains("root") {
+        return Err(SolveError::new(input, "a monkey named `root`"));
+    }
 
+    for name in jobs.iter().flat_map(|(_, operands)| operands) {
+        if !names.contains(name) {
+            return Err(SolveError::new(name, "the name of a defined monkey"));
+        }
+    }
+
+    let operands: HashMap<&str, [&str; 2]> = jobs.into_iter().collect();
+
+    // Jobs are made recursively, which would never end for a monkey waiting
+    // on itself. A monkey is waiting while the ones it needs are made.
+    let mut waiting = HashSet::new();
+    let mut done = HashSet::new();
+    let mut stack = vec![("root", false)];
+
+    while let Some((name, made)) = stack.pop() {
+        if made {
+            waiting.remove(name);
+            done.insert(name);
+            continue;
+        }
+
+        if done.contains(name) {
+            continue;
+        }
+
+        if !waiting.insert(name) {
+            return Err(SolveError::new(name, "a monkey not waiting on itself"));
+        }
+
+        stack.push((name, true));
+        stack.extend(
+            operands
+                .get(name)
+                .into_iter()
+                .flatten()
+                .map(|name| (*name, false)),
+        );
+    }
+
+    Ok(monkeys)
+}
+
+pub fn solve_1(monkeys: &[Monkey]) -> Answer {
     let mut jobs = HashMap::new();
 
-    let root_job = make_job(&"root".to_string(), &monkeys, &mut jobs)
+    let root_job = make_job(&"root".to_string(), monkeys, &mut jobs)
         .borrow()
         .root();
 
@@ -327,14 +411,12 @@ pub fn solve_1(input: &str) -> String {
 
     let (lhs, rhs) = root_job.yell(None);
 
-    (lhs + rhs).to_string()
+    (lhs + rhs).into()
 }
-pub fn solve_2(input: &str) -> String {
-    let monkeys: Vec<Monkey> = input.lines().map(Monkey::from).collect();
-
+pub fn solve_2(monkeys: &[Monkey]) -> Answer {
     let mut jobs = HashMap::new();
 
-    let root_job = make_job(&"root".to_string(), &monkeys, &mut jobs)
+    let root_job = make_job(&"root".to_string(), monkeys, &mut jobs)
         .borrow()
         .root();
 
@@ -342,5 +424,5 @@ pub fn solve_2(input: &str) -> String {
 
     let result = root_job.solve();
 
-    result.unwrap_or(0).to_string()
+    result.unwrap_or(0).into()
 }
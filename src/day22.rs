@@ -0,0 +1,511 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(22, "Monkey Map");
+
+const VOID: u8 = b' ';
+const OPEN: u8 = b'.';
+const WALL: u8 = b'#';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Facing {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+impl Facing {
+    const ALL: [Facing; 4] = [Facing::Right, Facing::Down, Facing::Left, Facing::Up];
+
+    fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Facing::Right => (1, 0),
+            Facing::Down => (0, 1),
+            Facing::Left => (-1, 0),
+            Facing::Up => (0, -1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Forward(usize),
+    TurnLeft,
+    TurnRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+    pub facing: Facing,
+}
+
+impl Position {
+    pub fn password(&self) -> usize {
+        1000 * (self.y + 1) + 4 * (self.x + 1) + self.facing as usize
+    }
+}
+
+pub struct Board {
+    rows: Vec<Vec<u8>>,
+    width: usize,
+    height: usize,
+}
+
+impl Board {
+    fn tile(&self, x: isize, y: isize) -> u8 {
+        if x < 0 || y < 0 {
+            return VOID;
+        }
+
+        self.rows
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+            .unwrap_or(VOID)
+    }
+
+    fn start(&self) -> Position {
+        let x = self.rows[0]
+            .iter()
+            .position(|tile| *tile == OPEN)
+            .unwrap_or(0);
+
+        Position {
+            x,
+            y: 0,
+            facing: Facing::Right,
+        }
+    }
+
+    /// Steps forward, wrapping around to the other side of the board.
+    fn step_flat(&self, position: Position) -> Position {
+        let (dx, dy) = position.facing.delta();
+        let (mut x, mut y) = (position.x as isize, position.y as isize);
+
+        loop {
+            x = (x + dx).rem_euclid(self.width as isize);
+            y = (y + dy).rem_euclid(self.height as isize);
+
+            if self.tile(x, y) != VOID {
+                return Position {
+                    x: x as usize,
+                    y: y as usize,
+                    ..position
+                };
+            }
+        }
+    }
+}
+
+/// A direction in three dimensions, along one of the axes.
+type Vector = [i8; 3];
+
+fn negate(vector: Vector) -> Vector {
+    vector.map(|component| -component)
+}
+
+/// Where one face of the net ends up on the cube: the directions its right
+/// and down edges point in, and its outward normal.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    right: Vector,
+    down: Vector,
+    normal: Vector,
+}
+
+impl Face {
+    fn direction(&self, facing: Facing) -> Vector {
+        match facing {
+            Facing::Right => self.right,
+            Facing::Down => self.down,
+            Facing::Left => negate(self.right),
+            Facing::Up => negate(self.down),
+        }
+    }
+
+    fn facing(&self, direction: Vector) -> Facing {
+        Facing::ALL
+            .into_iter()
+            .find(|facing| self.direction(*facing) == direction)
+            .expect("direction along the face")
+    }
+
+    /// The face on the other side of the edge in direction `facing`, folded
+    /// down by a right angle.
+    fn fold(&self, facing: Facing) -> Face {
+        let mut face = Face {
+            normal: self.direction(facing),
+            ..*self
+        };
+
+        // Walking on across the edge heads away from this face
+        match facing {
+            Facing::Right => face.right = negate(self.normal),
+            Facing::Left => face.right = self.normal,
+            Facing::Down => face.down = negate(self.normal),
+            Facing::Up => face.down = self.normal,
+        }
+
+        face
+    }
+}
+
+/// The board folded into a cube, found by walking the net from its first
+/// face and folding over every edge shared with another face.
+pub struct Cube {
+    size: usize,
+    faces: HashMap<(usize, usize), Face>,
+}
+
+impl Cube {
+    fn fold(board: &Board) -> Option<Self> {
+        let tiles = board
+            .rows
+            .iter()
+            .flatten()
+            .filter(|tile| **tile != VOID)
+            .count();
+        let size = (tiles / 6).isqrt();
+
+        if size == 0 || size * size * 6 != tiles {
+            return None;
+        }
+
+        let filled = |face_x: usize, face_y: usize| {
+            (0..size).all(|y| {
+                (0..size).all(|x| {
+                    board.tile((face_x * size + x) as isize, (face_y * size + y) as isize) != VOID
+                })
+            })
+        };
+
+        let nets: Vec<(usize, usize)> = (0..board.height.div_ceil(size))
+            .flat_map(|y| (0..board.width.div_ceil(size)).map(move |x| (x, y)))
+            .filter(|(x, y)| filled(*x, *y))
+            .collect();
+
+        if nets.len() != 6 {
+            return None;
+        }
+
+        let mut faces = HashMap::new();
+        let mut queue = VecDeque::from([(
+            nets[0],
+            Face {
+                right: [1, 0, 0],
+                down: [0, 1, 0],
+                normal: [0, 0, -1],
+            },
+        )]);
+
+        while let Some(((x, y), face)) = queue.pop_front() {
+            if faces.contains_key(&(x, y)) {
+                continue;
+            }
+
+            faces.insert((x, y), face);
+
+            for facing in Facing::ALL {
+                let (dx, dy) = facing.delta();
+                let next = (x.wrapping_add_signed(dx), y.wrapping_add_signed(dy));
+
+                if nets.contains(&next) {
+                    queue.push_back((next, face.fold(facing)));
+                }
+            }
+        }
+
+        // Disconnected faces, or two of them folding onto the same side
+        let mut normals: Vec<Vector> = faces.values().map(|face| face.normal).collect();
+        normals.sort();
+        normals.dedup();
+
+        (normals.len() == 6).then_some(Self { size, faces })
+    }
+
+    /// Steps forward, walking over the edge of the cube onto the next face
+    /// when the board ends.
+    fn step(&self, board: &Board, position: Position) -> Position {
+        let (dx, dy) = position.facing.delta();
+        let (x, y) = (position.x as isize + dx, position.y as isize + dy);
+
+        if board.tile(x, y) != VOID {
+            return Position {
+                x: x as usize,
+                y: y as usize,
+                ..position
+            };
+        }
+
+        let size = self.size;
+        let last = size - 1;
+        let face = self.faces[&(position.x / size, position.y / size)];
+        let (column, row) = (position.x % size, position.y % size);
+
+        let direction = face.direction(position.facing);
+        let (&(next_x, next_y), next) = self
+            .faces
+            .iter()
+            .find(|(_, next)| next.normal == direction)
+            .expect("a face on every side");
+
+        let facing = next.facing(negate(face.normal));
+
+        // The offset along the shared edge, measured along the same axis on
+        // both faces
+        let (offset, edge) = match position.facing {
+            Facing::Right | Facing::Left => (row, face.down),
+            Facing::Down | Facing::Up => (column, face.right),
+        };
+
+        let (mut column, mut row) = match edge {
+            edge if edge == next.right => (offset, 0),
+            edge if edge == negate(next.right) => (last - offset, 0),
+            edge if edge == next.down => (0, offset),
+            _ => (0, last - offset),
+        };
+
+        match facing {
+            Facing::Right => column = 0,
+            Facing::Left => column = last,
+            Facing::Down => row = 0,
+            Facing::Up => row = last,
+        }
+
+        Position {
+            x: next_x * size + column,
+            y: next_y * size + row,
+            facing,
+        }
+    }
+}
+
+pub struct Notes {
+    pub board: Board,
+    /// Only the second part walks on the cube, so a map that does not fold
+    /// into one is only an error there.
+    pub cube: Result<Cube, SolveError>,
+    pub path: Vec<Instruction>,
+}
+
+impl Notes {
+    fn walk(&self, step: impl Fn(Position) -> Position) -> Position {
+        let mut position = self.board.start();
+
+        for instruction in &self.path {
+            match instruction {
+                Instruction::TurnLeft => position.facing = position.facing.turn_left(),
+                Instruction::TurnRight => position.facing = position.facing.turn_right(),
+                Instruction::Forward(steps) => {
+                    for _ in 0..*steps {
+                        let next = step(position);
+
+                        if self.board.tile(next.x as isize, next.y as isize) == WALL {
+                            break;
+                        }
+
+                        position = next;
+                    }
+                }
+            }
+        }
+
+        position
+    }
+}
+
+fn parse_board(map: &[&str]) -> Result<Board, SolveError> {
+    let mut rows = vec![];
+
+    for line in map {
+        if let Some((index, tile)) = line
+            .char_indices()
+            .find(|(_, tile)| !matches!(tile, ' ' | '.' | '#'))
+        {
+            return Err(SolveError::new(
+                &line[index..index + tile.len_utf8()],
+                "a tile, ` `, `.` or `#`",
+            ));
+        }
+
+        rows.push(line.as_bytes().to_vec());
+    }
+
+    // The path starts on the top row, walking off an empty column would
+    // never find a tile again
+    if !rows[0].contains(&OPEN) {
+        return Err(SolveError::new(map[0], "a top row with an open tile `.`"));
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let height = rows.len();
+
+    for row in &mut rows {
+        row.resize(width, VOID);
+    }
+
+    Ok(Board {
+        rows,
+        width,
+        height,
+    })
+}
+
+fn parse_path(path: &str) -> Result<Vec<Instruction>, SolveError> {
+    let mut instructions = vec![];
+    let mut start = 0;
+
+    for (index, char) in path.char_indices() {
+        if char.is_ascii_digit() {
+            continue;
+        }
+
+        if start < index {
+            instructions.push(Instruction::Forward(error::number(&path[start..index])?));
+        }
+
+        instructions.push(match char {
+            'L' => Instruction::TurnLeft,
+            'R' => Instruction::TurnRight,
+            _ => {
+                return Err(SolveError::new(
+                    &path[index..index + char.len_utf8()],
+                    "a number of steps, `L` or `R`",
+                ))
+            }
+        });
+
+        start = index + char.len_utf8();
+    }
+
+    if start < path.len() {
+        instructions.push(Instruction::Forward(error::number(&path[start..])?));
+    }
+
+    Ok(instructions)
+}
+
+pub fn parse(input: &str) -> Result<Notes, SolveError> {
+    let mut lines = input.lines();
+    let map: Vec<&str> = lines.by_ref().take_while(|line| !line.is_empty()).collect();
+    let path = lines.find(|line| !line.is_empty());
+
+    let (false, Some(path)) = (map.is_empty(), path) else {
+        return Err(SolveError::new(
+            input,
+            "a map and a path separated by a blank line",
+        ));
+    };
+
+    let board = parse_board(&map)?;
+    let cube =
+        Cube::fold(&board).ok_or_else(|| SolveError::new(map[0], "a map that folds into a cube"));
+
+    Ok(Notes {
+        board,
+        cube,
+        path: parse_path(path.trim_end())?,
+    })
+}
+
+pub fn solve_1(notes: &Notes) -> Answer {
+    notes
+        .walk(|position| notes.board.step_flat(position))
+        .password()
+        .into()
+}
+
+pub fn solve_2(notes: &Notes) -> Result<Answer, SolveError> {
+    let cube = notes.cube.as_ref().map_err(Clone::clone)?;
+
+    Ok(notes
+        .walk(|position| cube.step(&notes.board, position))
+        .password()
+        .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, solve_1, solve_2, Facing, Position};
+
+    /// Walks a full circle around the cube from every tile and in every
+    /// direction, which must end up where it started for any net.
+    fn assert_circles(input: &str) {
+        let notes = parse(input).unwrap();
+        let board = &notes.board;
+        let cube = notes.cube.as_ref().unwrap();
+
+        for y in 0..board.height {
+            for x in 0..board.width {
+                if board.rows[y][x] == b' ' {
+                    continue;
+                }
+
+                for facing in Facing::ALL {
+                    let start = Position { x, y, facing };
+                    let mut position = start;
+
+                    for _ in 0..cube.size * 4 {
+                        position = cube.step(board, position);
+                    }
+
+                    assert_eq!(position, start);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn folds_any_net() {
+        assert_circles(include_str!("../example_input/day22.txt"));
+
+        // The layout most real inputs use
+        assert_circles(" ..\n .\n..\n.\n\n1");
+        assert_circles("..\n ..\n  ..\n\n1");
+    }
+
+    #[test]
+    fn rejects_maps_that_are_no_cube_in_part_2() {
+        let notes = parse("...\n...\n\n1").unwrap();
+        assert_eq!(solve_1(&notes), "1008");
+        assert_eq!(
+            solve_2(&notes).unwrap_err().expected,
+            "a map that folds into a cube"
+        );
+
+        let notes = parse("..\n.\n..\n.\n\n1").unwrap();
+        assert!(solve_2(&notes).is_err());
+
+        let error = parse("..\n ..\n  ..\n\n10X").err().unwrap();
+        assert_eq!(error.text, "X");
+    }
+
+    #[test]
+    fn reads_any_line_ending() {
+        let input = include_str!("../example_input/day22.txt").replace('\n', "\r\n");
+        let notes = parse(&input).unwrap();
+
+        assert_eq!(solve_1(&notes), "6032");
+        assert_eq!(solve_2(&notes).unwrap(), "5031");
+
+        let error = parse(" ..\n#.é\n\n1").err().unwrap();
+        assert_eq!(error.text, "é");
+        assert_eq!(parse("  #\n\n1").err().unwrap().text, "  #");
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    answers::Answer,
+    error::SolveError,
+    extra::{visualize, ChristmasGraph, Color, GraphMetadata, Style},
+    record::Recorder,
+    solver::{solution, Cancel},
+};
+
+solution!(23, "Unstable Diffusion", cancellable);
+
+const NEIGHBORS: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// The three tiles an elf looks at before proposing to move in a direction,
+/// the move itself being the one in the middle.
+const DIRECTIONS: [[(i64, i64); 3]; 4] = [
+    [(-1, -1), (0, -1), (1, -1)],
+    [(-1, 1), (0, 1), (1, 1)],
+    [(-1, -1), (-1, 0), (-1, 1)],
+    [(1, -1), (1, 0), (1, 1)],
+];
+
+#[derive(Clone)]
+pub struct Diffusion {
+    pub elves: HashSet<(i64, i64)>,
+    pub rounds: usize,
+}
+
+impl Diffusion {
+    fn proposal(&self, (x, y): (i64, i64)) -> Option<(i64, i64)> {
+        let occupied = |(dx, dy): &(i64, i64)| self.elves.contains(&(x + dx, y + dy));
+
+        if !NEIGHBORS.iter().any(occupied) {
+            return None;
+        }
+
+        (0..DIRECTIONS.len())
+            .map(|index| DIRECTIONS[(self.rounds + index) % DIRECTIONS.len()])
+            .find(|direction| !direction.iter().any(occupied))
+            .map(|[_, (dx, dy), _]| (x + dx, y + dy))
+    }
+
+    /// Plays one round, returning whether any elf moved.
+    pub fn round(&mut self) -> bool {
+        let mut proposals: HashMap<(i64, i64), Vec<(i64, i64)>> = HashMap::new();
+
+        for elf in &self.elves {
+            if let Some(target) = self.proposal(*elf) {
+                proposals.entry(target).or_default().push(*elf);
+            }
+        }
+
+        let mut moved = false;
+
+        for (target, elves) in proposals {
+            if let [elf] = elves[..] {
+                self.elves.remove(&elf);
+                self.elves.insert(target);
+                moved = true;
+            }
+        }
+
+        self.rounds += 1;
+
+        moved
+    }
+
+    fn bounds(&self) -> ((i64, i64), (i64, i64)) {
+        let xs = self.elves.iter().map(|(x, _)| *x);
+        let ys = self.elves.iter().map(|(_, y)| *y);
+
+        (
+            (xs.clone().min().unwrap_or(0), ys.clone().min().unwrap_or(0)),
+            (xs.max().unwrap_or(0), ys.max().unwrap_or(0)),
+        )
+    }
+
+    pub fn empty_tiles(&self) -> usize {
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds();
+        let area = (max_x - min_x + 1) * (max_y - min_y + 1);
+
+        area as usize - self.elves.len()
+    }
+}
+
+impl ChristmasGraph for Diffusion {
+    fn as_graph_metadata(&self) -> GraphMetadata {
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds();
+
+        let legend_y_width = min_y.to_string().len().max(max_y.to_string().len()) as u32;
+        let legend_x_width = min_x.to_string().len().max(max_x.to_string().len()) as u32;
+
+        GraphMetadata {
+            x: min_x,
+            y: min_y,
+            width: (max_x - min_x + 1) as u32,
+            height: (max_y - min_y + 1) as u32,
+            legend_step_x: 5,
+            legend_step_y: 1,
+            legend_y_width,
+            legend_x_width,
+        }
+    }
+
+    fn graph_legend_x(&self, value: i64) -> Vec<char> {
+        value.to_string().chars().collect()
+    }
+
+    fn graph_legend_y(&self, value: i64) -> Vec<char> {
+        value.to_string().chars().collect()
+    }
+
+    fn graph_value(&self, x: i64, y: i64) -> Option<char> {
+        self.elves.contains(&(x, y)).then_some('#')
+    }
+
+    fn graph_style(&self, _x: i64, _y: i64) -> Style {
+        Style::color(Color::Green)
+    }
+}
+
+impl Display for Diffusion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "after {} rounds:", self.rounds)?;
+        ChristmasGraph::fmt(self, f)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Diffusion, SolveError> {
+    let mut elves = HashSet::new();
+
+    for (y, line) in input.lines().enumerate() {
+        for (x, (index, tile)) in line.char_indices().enumerate() {
+            match tile {
+                '#' => {
+                    elves.insert((x as i64, y as i64));
+                }
+                '.' => {}
+                _ => {
+                    return Err(SolveError::new(
+                        &line[index..index + tile.len_utf8()],
+                        "an elf `#` or ground `.`",
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(Diffusion { elves, rounds: 0 })
+}
+
+pub fn solve_1(diffusion: &Diffusion, cancel: &Cancel) -> Answer {
+    let mut diffusion = diffusion.clone();
+
+    visualize(23, 1, &diffusion);
+
+    let mut recorder = Recorder::new(23, 1);
+    recorder.record(&diffusion);
+
+    while diffusion.rounds < 10 && !cancel.is_cancelled() {
+        diffusion.round();
+        recorder.record(&diffusion);
+    }
+
+    recorder.finish(&diffusion);
+    visualize(23, 1, &diffusion);
+
+    diffusion.empty_tiles().into()
+}
+
+pub fn solve_2(diffusion: &Diffusion, cancel: &Cancel) -> Answer {
+    let mut diffusion = diffusion.clone();
+
+    let mut recorder = Recorder::new(23, 2);
+    recorder.record(&diffusion);
+
+    while diffusion.round() && !cancel.is_cancelled() {
+        recorder.record(&diffusion);
+    }
+
+    recorder.finish(&diffusion);
+    visualize(23, 2, &diffusion);
+
+    diffusion.rounds.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, solve_1, solve_2};
+    use crate::solver::Cancel;
+
+    #[test]
+    fn spreads_out_small_and_large_groups() {
+        let cancel = Cancel::never();
+
+        let diffusion = parse(".....\n..##.\n..#..\n.....\n..##.\n.....\n").unwrap();
+        assert_eq!(solve_1(&diffusion, &cancel), "25");
+        assert_eq!(solve_2(&diffusion, &cancel), "4");
+
+        let diffusion = parse(include_str!("../example_input/day23.txt")).unwrap();
+        assert_eq!(diffusion.elves.len(), 22);
+        assert_eq!(solve_1(&diffusion, &cancel), "110");
+        assert_eq!(solve_2(&diffusion, &cancel), "20");
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::{
+    answers::Answer,
+    error::SolveError,
+    solver::{solution, Cancel},
+};
+
+solution!(24, "Blizzard Basin", cancellable);
+
+const MOVES: [(i64, i64); 5] = [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)];
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The valley without its walls. The entrance sits just above the first row
+/// and the exit just below the last one.
+pub struct Valley {
+    width: usize,
+    height: usize,
+    tiles: Vec<Vec<u8>>,
+    entrance: (i64, i64),
+    exit: (i64, i64),
+}
+
+impl Valley {
+    /// Blizzards return to their starting positions after this many minutes,
+    /// which bounds the states worth searching.
+    fn period(&self) -> usize {
+        self.width / gcd(self.width, self.height) * self.height
+    }
+
+    /// Whether `(x, y)` is free of blizzards at `minute`, found by looking
+    /// back at where each kind of blizzard would have had to start.
+    fn is_free(&self, (x, y): (i64, i64), minute: usize) -> bool {
+        if (x, y) == self.entrance || (x, y) == self.exit {
+            return true;
+        }
+
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return false;
+        }
+
+        let (width, height) = (self.width as i64, self.height as i64);
+        let minute = minute as i64;
+        let tile = |x: i64, y: i64| {
+            self.tiles[y.rem_euclid(height) as usize][x.rem_euclid(width) as usize]
+        };
+
+        tile(x - minute, y) != b'>'
+            && tile(x + minute, y) != b'<'
+            && tile(x, y - minute) != b'v'
+            && tile(x, y + minute) != b'^'
+    }
+
+    fn index(&self, (x, y): (i64, i64)) -> usize {
+        // Row 0 is the entrance, the last row the exit
+        (y + 1) as usize * self.width + x as usize
+    }
+
+    /// The minute of arrival at `to` when leaving `from` at `minute`, by a
+    /// breadth first search over positions and minutes within the period.
+    /// Only the states reached are remembered, as the period of a valley
+    /// with coprime sides grows with its area.
+    pub fn cross(
+        &self,
+        from: (i64, i64),
+        to: (i64, i64),
+        minute: usize,
+        cancel: &Cancel,
+    ) -> Option<usize> {
+        let period = self.period();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([(from, minute)]);
+
+        while let Some((position, minute)) = queue.pop_front() {
+            if position == to {
+                return Some(minute);
+            }
+
+            if cancel.is_cancelled() {
+                return None;
+            }
+
+            let next = minute + 1;
+
+            for (dx, dy) in MOVES {
+                let step = (position.0 + dx, position.1 + dy);
+
+                if !self.is_free(step, next) {
+                    continue;
+                }
+
+                if seen.insert((next % period, self.index(step))) {
+                    queue.push_back((step, next));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+pub fn parse(input: &str) -> Result<Valley, SolveError> {
+    let lines: Vec<&str> = input.lines().collect();
+
+    let (Some(first), Some(last)) = (lines.first(), lines.last()) else {
+        return Err(SolveError::new(input, "a valley surrounded by walls"));
+    };
+
+    if lines.len() < 3 || first.len() < 3 {
+        return Err(SolveError::new(first, "a valley surrounded by walls"));
+    }
+
+    let width = first.len() - 2;
+    let height = lines.len() - 2;
+    let opening = |line: &str| {
+        line.find('.')
+            .filter(|x| (1..=width).contains(x))
+            .map(|x| x as i64 - 1)
+            .ok_or_else(|| SolveError::new(line, "a wall with one opening"))
+    };
+
+    let entrance = (opening(first)?, -1);
+    let exit = (opening(last)?, height as i64);
+
+    let mut tiles = vec![];
+
+    for line in &lines[1..lines.len() - 1] {
+        if line.len() != width + 2 || !line.starts_with('#') || !line.ends_with('#') {
+            return Err(SolveError::new(
+                line,
+                format!("a row of {} tiles between walls", width),
+            ));
+        }
+
+        let row = &line[1..=width];
+
+        if let Some((index, tile)) = row
+            .char_indices()
+            .find(|(_, tile)| !".<>^v".contains(*tile))
+        {
+            return Err(SolveError::new(
+                &row[index..index + tile.len_utf8()],
+                "ground `.` or a blizzard `<`, `>`, `^` or `v`",
+            ));
+        }
+
+        tiles.push(row.as_bytes().to_vec());
+    }
+
+    Ok(Valley {
+        width,
+        height,
+        tiles,
+        entrance,
+        exit,
+    })
+}
+
+fn arrival(minute: Option<usize>, cancel: &Cancel) -> Answer {
+    match minute {
+        Some(minute) => minute.into(),
+        // The runner discards the answer of a cancelled search
+        None if cancel.is_cancelled() => 0.into(),
+        None => panic!("no way through the valley"),
+    }
+}
+
+pub fn solve_1(valley: &Valley, cancel: &Cancel) -> Answer {
+    arrival(
+        valley.cross(valley.entrance, valley.exit, 0, cancel),
+        cancel,
+    )
+}
+
+pub fn solve_2(valley: &Valley, cancel: &Cancel) -> Answer {
+    let there = valley.cross(valley.entrance, valley.exit, 0, cancel);
+    let back = there.and_then(|minute| valley.cross(valley.exit, valley.entrance, minute, cancel));
+    let again = back.and_then(|minute| valley.cross(valley.entrance, valley.exit, minute, cancel));
+
+    arrival(again, cancel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, solve_1};
+    use crate::solver::Cancel;
+
+    #[test]
+    fn valleys_of_any_size() {
+        let valley = parse(include_str!("../example_input/day24.txt")).unwrap();
+        assert_eq!(valley.period(), 12);
+
+        let valley = parse("#.###\n#...#\n###.#\n").unwrap();
+        assert_eq!(valley.period(), 3);
+        assert_eq!(solve_1(&valley, &Cancel::never()), "4");
+
+        let valley = parse("#.####\n#.<..#\n#>...#\n#..v.#\n####.#\n").unwrap();
+        assert_eq!(valley.period(), 12);
+        assert_eq!(solve_1(&valley, &Cancel::never()), "9");
+
+        // Coprime sides make a period as large as the valley
+        let row = format!("#{}#\n", ".".repeat(31));
+        let input = format!(
+            "#.{}\n{}#{}.#\n",
+            "#".repeat(31),
+            row.repeat(30),
+            "#".repeat(30)
+        );
+        let valley = parse(&input).unwrap();
+        assert_eq!(valley.period(), 31 * 30);
+        assert_eq!(solve_1(&valley, &Cancel::never()), "61");
+
+        let error = parse("#.###\n#.é#\n###.#\n").err().unwrap();
+        assert_eq!(error.text, "é");
+    }
+}
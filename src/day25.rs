@@ -0,0 +1,299 @@
+use std::{
+    fmt::{self, Display},
+    iter::Sum,
+    ops::{Add, Neg, Sub},
+};
+
+use crate::{answers::Answer, error::SolveError, solver::solution};
+
+solution!(25, "Full of Hot Air");
+
+const DIGITS: [char; 5] = ['=', '-', '0', '1', '2'];
+
+/// A balanced base five number, written with the digits `=`, `-`, `0`, `1`
+/// and `2` for -2 to 2.
+///
+/// The digits are kept least significant first without leading zeros, so
+/// zero has no digits and equal numbers compare equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Snafu {
+    digits: Vec<i8>,
+}
+
+impl Snafu {
+    fn normalized(mut digits: Vec<i8>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        Self { digits }
+    }
+}
+
+impl TryFrom<&str> for Snafu {
+    type Error = SolveError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if value.is_empty() {
+            return Err(SolveError::new(value, "a SNAFU number"));
+        }
+
+        let mut digits = Vec::with_capacity(value.len());
+
+        for (index, char) in value.char_indices().rev() {
+            match DIGITS.iter().position(|digit| *digit == char) {
+                Some(position) => digits.push(position as i8 - 2),
+                None => {
+                    return Err(SolveError::new(
+                        &value[index..index + char.len_utf8()],
+                        "a SNAFU digit, `=`, `-`, `0`, `1` or `2`",
+                    ))
+                }
+            }
+        }
+
+        Ok(Self::normalized(digits))
+    }
+}
+
+impl Display for Snafu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.digits.is_empty() {
+            return write!(f, "0");
+        }
+
+        for digit in self.digits.iter().rev() {
+            write!(f, "{}", DIGITS[(digit + 2) as usize])?;
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! snafu_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Snafu {
+                fn from(mut value: $integer) -> Self {
+                    let mut digits = vec![];
+
+                    while value != 0 {
+                        // Remainders of 3 and 4 borrow from the next digit
+                        let digit = match value.rem_euclid(5) {
+                            remainder @ 0..=2 => remainder,
+                            remainder => remainder - 5,
+                        };
+
+                        digits.push(digit as i8);
+                        value = value.div_euclid(5) + (digit < 0) as $integer;
+                    }
+
+                    Self { digits }
+                }
+            }
+
+            impl TryFrom<&Snafu> for $integer {
+                type Error = String;
+
+                fn try_from(value: &Snafu) -> Result<Self, Self::Error> {
+                    // Five times a prefix can overflow right before its next
+                    // digit brings the number back into range, as close to
+                    // the limits of the type. Adding the digit in between
+                    // four and five times the prefix only overflows when the
+                    // number does not fit.
+                    value
+                        .digits
+                        .iter()
+                        .rev()
+                        .try_fold(0 as $integer, |number, digit| {
+                            number
+                                .checked_mul(4)?
+                                .checked_add(*digit as $integer)?
+                                .checked_add(number)
+                        })
+                        .ok_or_else(|| {
+                            format!("{} does not fit into {}", value, stringify!($integer))
+                        })
+                }
+            }
+        )*
+    };
+}
+
+snafu_integer!(i64, i128);
+
+/// Adds digit by digit, carrying whenever a sum leaves the range of a digit.
+impl Add<&Snafu> for &Snafu {
+    type Output = Snafu;
+
+    fn add(self, other: &Snafu) -> Snafu {
+        let length = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(length + 1);
+        let mut carry = 0;
+
+        for index in 0..length {
+            let left = self.digits.get(index).copied().unwrap_or(0);
+            let right = other.digits.get(index).copied().unwrap_or(0);
+            let digit;
+            (carry, digit) = match left + right + carry {
+                sum @ 3.. => (1, sum - 5),
+                sum @ ..=-3 => (-1, sum + 5),
+                sum => (0, sum),
+            };
+
+            digits.push(digit);
+        }
+
+        digits.push(carry);
+
+        Snafu::normalized(digits)
+    }
+}
+
+impl Add for Snafu {
+    type Output = Snafu;
+
+    fn add(self, other: Snafu) -> Snafu {
+        &self + &other
+    }
+}
+
+impl Neg for &Snafu {
+    type Output = Snafu;
+
+    fn neg(self) -> Snafu {
+        Snafu {
+            digits: self.digits.iter().map(|digit| -digit).collect(),
+        }
+    }
+}
+
+impl Neg for Snafu {
+    type Output = Snafu;
+
+    fn neg(self) -> Snafu {
+        -&self
+    }
+}
+
+impl Sub<&Snafu> for &Snafu {
+    type Output = Snafu;
+
+    fn sub(self, other: &Snafu) -> Snafu {
+        self + &-other
+    }
+}
+
+impl Sub for Snafu {
+    type Output = Snafu;
+
+    fn sub(self, other: Snafu) -> Snafu {
+        &self - &other
+    }
+}
+
+impl<'a> Sum<&'a Snafu> for Snafu {
+    fn sum<I: Iterator<Item = &'a Snafu>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |sum, number| &sum + number)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Snafu>, SolveError> {
+    input.lines().map(Snafu::try_from).collect()
+}
+
+pub fn solve_1(numbers: &[Snafu]) -> Answer {
+    numbers.iter().sum::<Snafu>().to_string().into()
+}
+
+/// The last star comes for free once all the others are collected.
+pub fn solve_2(_numbers: &[Snafu]) -> Answer {
+    "Start the blender".into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Snafu;
+
+    /// A small linear congruential generator, enough to spread the property
+    /// tests over the whole range without a dependency.
+    fn numbers(seed: u64) -> impl Iterator<Item = i64> {
+        let mut state = seed;
+
+        std::iter::repeat_with(move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state as i64
+        })
+    }
+
+    fn snafu(text: &str) -> Snafu {
+        Snafu::try_from(text).unwrap()
+    }
+
+    #[test]
+    fn converts_the_examples() {
+        let examples = [
+            (0i64, "0"),
+            (1, "1"),
+            (2, "2"),
+            (3, "1="),
+            (4, "1-"),
+            (5, "10"),
+            (8, "2="),
+            (10, "20"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+            (-3, "-2"),
+        ];
+
+        for (number, text) in examples {
+            assert_eq!(Snafu::from(number).to_string(), text);
+            assert_eq!(i64::try_from(&snafu(text)), Ok(number));
+        }
+
+        assert_eq!(snafu("00-1"), snafu("-1"));
+        assert_eq!(Snafu::try_from("1=3").unwrap_err().text, "3");
+        assert!(Snafu::try_from("").is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let small = -100_000..100_000;
+        let large = numbers(25).take(100_000);
+        let extremes = [i64::MIN, i64::MIN + 1, i64::MAX - 1, i64::MAX];
+
+        for number in small.chain(large).chain(extremes) {
+            let text = Snafu::from(number).to_string();
+
+            assert_eq!(i64::try_from(&snafu(&text)), Ok(number));
+            assert_eq!(Snafu::from(number as i128), snafu(&text));
+        }
+
+        for number in [i128::MIN, i128::MIN + 1, i128::MAX] {
+            assert_eq!(i128::try_from(&Snafu::from(number)), Ok(number));
+        }
+
+        let too_large = Snafu::from(i64::MAX as i128 + 1);
+        assert_eq!(i128::try_from(&too_large), Ok(i64::MAX as i128 + 1));
+        assert!(i64::try_from(&too_large).is_err());
+        assert!(i128::try_from(&(Snafu::from(i128::MAX) + Snafu::from(1i64))).is_err());
+        assert!(i128::try_from(&(Snafu::from(i128::MIN) - Snafu::from(1i64))).is_err());
+    }
+
+    #[test]
+    fn adds_in_snafu_digits() {
+        let mut numbers = numbers(2022).map(|number| number >> 2);
+
+        for _ in 0..100_000 {
+            let (left, right) = (numbers.next().unwrap(), numbers.next().unwrap());
+            let sum = Snafu::from(left) + Snafu::from(right);
+            let difference = Snafu::from(left) - Snafu::from(right);
+
+            assert_eq!(i64::try_from(&sum), Ok(left + right));
+            assert_eq!(i64::try_from(&difference), Ok(left - right));
+        }
+    }
+}
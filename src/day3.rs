@@ -1,5 +1,9 @@
 use std::collections::HashSet;
 
+use crate::{answers::Answer, error::SolveError, solver::solution};
+
+solution!(3, "Rucksack Reorganization");
+
 fn split_rucksack_line(line: &str) -> (&str, &str) {
     line.split_at(line.len() / 2)
 }
@@ -22,20 +26,38 @@ pub fn priority(char: &char) -> u32 {
         .expect("find item priority")
 }
 
-pub fn solve_1(input: &str) -> String {
-    let errors = input.lines().map(|line| {
+pub fn parse(input: &str) -> Result<Vec<String>, SolveError> {
+    input
+        .lines()
+        .map(|line| {
+            match line
+                .char_indices()
+                .find(|(_, item)| !item.is_ascii_alphabetic())
+            {
+                Some((index, item)) => Err(SolveError::new(
+                    &line[index..index + item.len_utf8()],
+                    "an item letter",
+                )),
+                None => Ok(line.to_string()),
+            }
+        })
+        .collect()
+}
+
+pub fn solve_1(rucksacks: &[String]) -> Answer {
+    let errors = rucksacks.iter().map(|line| {
         let (l, r) = split_rucksack_line(line);
         find_error(l, r)
     });
 
     let priorities = errors.map(|error| priority(&error));
 
-    priorities.sum::<u32>().to_string()
+    priorities.sum::<u32>().into()
 }
 
-pub fn solve_2(input: &str) -> String {
-    let char_sets: Vec<HashSet<char>> = input
-        .lines()
+pub fn solve_2(rucksacks: &[String]) -> Answer {
+    let char_sets: Vec<HashSet<char>> = rucksacks
+        .iter()
         .map(|line| HashSet::from_iter(line.chars()))
         .collect();
 
@@ -52,5 +74,5 @@ pub fn solve_2(input: &str) -> String {
             _ => unreachable!(),
         })
         .sum::<u32>()
-        .to_string()
+        .into()
 }
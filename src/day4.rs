@@ -1,52 +1,46 @@
 use std::collections::HashSet;
 
-pub fn left_right_sets_from_line(line: &str) -> (HashSet<u32>, HashSet<u32>) {
-    let (left, right) = line.split_once(',').expect("split section assignment");
-    let (left_start, left_end): (u32, u32) = left
-        .split_once('-')
-        .map(|(left, right)| {
-            (
-                left.parse().expect("parse min"),
-                right.parse().expect("parse max"),
-            )
-        })
-        .expect("split section range");
-    let (right_start, right_end): (u32, u32) = right
-        .split_once('-')
-        .map(|(left, right)| {
-            (
-                left.parse().expect("parse min"),
-                right.parse().expect("parse max"),
-            )
-        })
-        .expect("split section range");
-
-    let left: HashSet<u32> = HashSet::from_iter(left_start..=left_end);
-    let right: HashSet<u32> = HashSet::from_iter(right_start..=right_end);
-
-    (left, right)
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(4, "Camp Cleanup");
+
+/// The sections assigned to both elves of a pair.
+pub type SectionPair = (HashSet<u32>, HashSet<u32>);
+
+fn section_range(range: &str) -> Result<HashSet<u32>, SolveError> {
+    let (start, end) = error::split_once(range, "-", "a section range like `2-4`")?;
+
+    Ok(HashSet::from_iter(
+        error::number::<u32>(start)?..=error::number(end)?,
+    ))
 }
 
-pub fn solve_1(input: &str) -> String {
-    input
-        .lines()
-        .map(|line| {
-            let (left, right) = left_right_sets_from_line(line);
+pub fn left_right_sets_from_line(line: &str) -> Result<SectionPair, SolveError> {
+    let (left, right) = error::split_once(line, ",", "two section assignments")?;
 
-            usize::from(left.is_superset(&right) || left.is_subset(&right))
-        })
-        .sum::<usize>()
-        .to_string()
+    Ok((section_range(left)?, section_range(right)?))
 }
 
-pub fn solve_2(input: &str) -> String {
-    input
-        .lines()
-        .map(|line| {
-            let (left, right) = left_right_sets_from_line(line);
+pub fn parse(input: &str) -> Result<Vec<SectionPair>, SolveError> {
+    input.lines().map(left_right_sets_from_line).collect()
+}
+
+pub fn solve_1(pairs: &[SectionPair]) -> Answer {
+    pairs
+        .iter()
+        .map(|(left, right)| usize::from(left.is_superset(right) || left.is_subset(right)))
+        .sum::<usize>()
+        .into()
+}
 
-            usize::from(!left.is_disjoint(&right))
-        })
+pub fn solve_2(pairs: &[SectionPair]) -> Answer {
+    pairs
+        .iter()
+        .map(|(left, right)| usize::from(!left.is_disjoint(right)))
         .sum::<usize>()
-        .to_string()
+        .into()
 }
@@ -1,39 +1,47 @@
 use std::{collections::VecDeque, vec};
 
-pub fn read_crate_line(line: &str) -> Vec<Option<char>> {
-    let char_vector: Vec<char> = line.chars().collect();
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(5, "Supply Stacks");
+
+pub fn read_crate_line(line: &str) -> Result<Vec<Option<char>>, SolveError> {
+    let char_vector: Vec<(usize, char)> = line.char_indices().collect();
     let chunks = char_vector.chunks(4);
 
     let mut values = vec![];
 
     for chunk in chunks {
-        if chunk[0] == '[' {
-            values.push(Some(chunk[1]));
-        } else {
-            values.push(None)
+        match chunk {
+            [(_, '['), (_, value), (_, ']'), ..] => values.push(Some(*value)),
+            [(start, '['), .., (end, last)] => {
+                return Err(SolveError::new(
+                    &line[*start..end + last.len_utf8()],
+                    "a crate like `[A]`",
+                ))
+            }
+            _ => values.push(None),
         }
     }
 
-    values
+    Ok(values)
 }
 
-pub fn read_crates(input: &str) -> Vec<VecDeque<char>> {
+pub fn read_crates(input: &str) -> Result<Vec<VecDeque<char>>, SolveError> {
     let mut crate_lines: Vec<Vec<Option<char>>> = vec![];
     let mut number_of_crates = 0;
 
     for line in input.lines() {
         if line.starts_with(" 1") {
-            number_of_crates = line
-                .split(' ')
-                .last()
-                .expect("read last crate number")
-                .parse()
-                .expect("parse last crate number");
+            number_of_crates = error::number(line.split(' ').next_back().unwrap_or(line))?;
 
             break;
         }
 
-        crate_lines.push(read_crate_line(line))
+        crate_lines.push(read_crate_line(line)?)
     }
 
     let mut crates: Vec<VecDeque<char>> = Vec::with_capacity(number_of_crates);
@@ -50,30 +58,65 @@ pub fn read_crates(input: &str) -> Vec<VecDeque<char>> {
         crates.push(crate_vector);
     }
 
-    crates
+    Ok(crates)
 }
 
-pub fn read_moves(input: &str) -> Vec<(usize, usize, usize)> {
+pub fn read_moves(
+    input: &str,
+    crates: &[VecDeque<char>],
+) -> Result<Vec<(usize, usize, usize)>, SolveError> {
     let mut output = vec![];
+    let stacks = crates.len();
+
+    // Both cranes move the same number of crates, so the heights of the
+    // stacks are known before solving
+    let mut heights: Vec<usize> = crates.iter().map(VecDeque::len).collect();
+
+    let stack = |text: &str| {
+        error::number(text).and_then(|stack| match (1..=stacks).contains(&stack) {
+            true => Ok(stack),
+            false => Err(SolveError::new(
+                text,
+                format!("a stack between 1 and {}", stacks),
+            )),
+        })
+    };
 
     for line in input.lines() {
         if !line.starts_with("move") {
             continue;
         }
 
-        let line = line.strip_prefix("move ").expect("strip prefix");
-        let (move_count_str, rest) = line.split_once(' ').expect("split move count");
-        let move_count: usize = move_count_str.parse().expect("parse move count");
-        let line = rest.strip_prefix("from ").expect("split prefix");
-        let (move_from_str, rest) = line.split_once(' ').expect("split move to");
-        let move_from: usize = move_from_str.parse().expect("parse move to");
-        let line = rest.strip_prefix("to ").expect("strip prefix");
-        let move_to: usize = line.parse().expect("parse move to");
+        let line = error::strip_prefix(line, "move ")?;
+        let (move_count_str, rest) = error::split_once(line, " ", "a crate count")?;
+        let move_count: usize = error::number(move_count_str)?;
+        let line = error::strip_prefix(rest, "from ")?;
+        let (move_from_str, rest) = error::split_once(line, " ", "a stack to move from")?;
+        let move_from = stack(move_from_str)?;
+        let line = error::strip_prefix(rest, "to ")?;
+        let move_to = stack(line)?;
+
+        if move_to == move_from {
+            return Err(SolveError::new(
+                line,
+                "a stack other than the one to move from",
+            ));
+        }
+
+        if move_count > heights[move_from - 1] {
+            return Err(SolveError::new(
+                move_count_str,
+                format!("at most {} crates", heights[move_from - 1]),
+            ));
+        }
+
+        heights[move_from - 1] -= move_count;
+        heights[move_to - 1] += move_count;
 
         output.push((move_count, move_from, move_to))
     }
 
-    output
+    Ok(output)
 }
 
 pub fn perform_move(
@@ -85,7 +128,7 @@ pub fn perform_move(
     let mut to_crate = crates[to - 1].clone();
 
     for _ in 0..count {
-        let value = from_crate.pop_front().expect("crate contents");
+        let value = from_crate.pop_front().expect("moves checked while parsing");
         to_crate.push_front(value);
     }
 
@@ -118,15 +161,26 @@ pub fn crates_front_string(crates: Vec<VecDeque<char>>) -> String {
     output
 }
 
-pub fn solve_1(input: &str) -> String {
-    let mut crates = read_crates(input);
-    let move_instructions = read_moves(input);
+pub struct Procedure {
+    pub crates: Vec<VecDeque<char>>,
+    pub moves: Vec<(usize, usize, usize)>,
+}
+
+pub fn parse(input: &str) -> Result<Procedure, SolveError> {
+    let crates = read_crates(input)?;
+    let moves = read_moves(input, &crates)?;
+
+    Ok(Procedure { crates, moves })
+}
+
+pub fn solve_1(procedure: &Procedure) -> Answer {
+    let mut crates = procedure.crates.clone();
 
-    for move_instruction in move_instructions {
+    for move_instruction in procedure.moves.iter().copied() {
         crates = perform_move(crates, move_instruction);
     }
 
-    crates_front_string(crates)
+    crates_front_string(crates).into()
 }
 
 pub fn perform_move_2(
@@ -140,7 +194,7 @@ pub fn perform_move_2(
     let mut to_move: Vec<char> = vec![];
 
     for _ in 0..count {
-        let value = from_crate.pop_front().expect("crate contents");
+        let value = from_crate.pop_front().expect("moves checked while parsing");
         to_move.push(value);
     }
 
@@ -165,13 +219,12 @@ pub fn perform_move_2(
     new
 }
 
-pub fn solve_2(input: &str) -> String {
-    let mut crates = read_crates(input);
-    let move_instructions = read_moves(input);
+pub fn solve_2(procedure: &Procedure) -> Answer {
+    let mut crates = procedure.crates.clone();
 
-    for move_instruction in move_instructions {
+    for move_instruction in procedure.moves.iter().copied() {
         crates = perform_move_2(crates, move_instruction);
     }
 
-    crates_front_string(crates)
+    crates_front_string(crates).into()
 }
@@ -1,43 +1,50 @@
 use std::collections::HashSet;
 
-pub fn solve_1(input: &str) -> String {
-    let found = input
-        .chars()
-        .collect::<Vec<char>>()
-        .windows(4)
-        .map(|chars| {
-            let mut set: HashSet<char> = HashSet::new();
-
-            chars.iter().for_each(|char| {
-                set.insert(*char);
-            });
-
-            set
-        })
-        .enumerate()
-        .find(|(_, set)| set.len() == 4)
-        .expect("solution");
+use crate::{answers::Answer, error::SolveError, solver::solution};
+
+solution!(6, "Tuning Trouble");
+
+/// The number of characters processed before each marker.
+pub struct Signal {
+    start_of_packet: usize,
+    start_of_message: usize,
+}
 
-    (found.0 + 4).to_string()
+/// The position right after the first `length` characters that are all
+/// different.
+fn find_marker(signal: &[char], length: usize) -> Option<usize> {
+    signal
+        .windows(length)
+        .position(|chars| chars.iter().collect::<HashSet<_>>().len() == length)
+        .map(|position| position + length)
 }
 
-pub fn solve_2(input: &str) -> String {
-    let found = input
-        .chars()
-        .collect::<Vec<char>>()
-        .windows(14)
-        .map(|chars| {
-            let mut set = HashSet::<char>::new();
+pub fn parse(input: &str) -> Result<Signal, SolveError> {
+    let line = input.trim_end();
+    let signal: Vec<char> = line.chars().collect();
+    let end = &line[line.len()..];
 
-            for char in chars {
-                set.insert(*char);
-            }
+    if signal.len() < 14 {
+        return Err(SolveError::new(end, "at least 14 characters"));
+    }
 
-            set
+    // A start-of-message marker also contains a start-of-packet marker
+    let marker = |length| {
+        find_marker(&signal, length).ok_or_else(|| {
+            SolveError::new(end, format!("{} different characters in a row", length))
         })
-        .enumerate()
-        .find(|(_, set)| set.len() == 14)
-        .expect("solution");
+    };
+
+    Ok(Signal {
+        start_of_packet: marker(4)?,
+        start_of_message: marker(14)?,
+    })
+}
+
+pub fn solve_1(signal: &Signal) -> Answer {
+    signal.start_of_packet.into()
+}
 
-    (found.0 + 14).to_string()
+pub fn solve_2(signal: &Signal) -> Answer {
+    signal.start_of_message.into()
 }
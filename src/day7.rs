@@ -1,5 +1,16 @@
 use std::{collections::HashMap, path::PathBuf};
 
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(7, "No Space Left On Device");
+
+const TOTAL_SPACE: usize = 70000000;
+const NEEDED_SPACE: usize = 30000000;
+
 #[derive(Debug)]
 pub struct Listing {
     path: PathBuf,
@@ -52,24 +63,29 @@ impl Directory {
     }
 }
 
-pub fn parse_file(line: &str) -> Option<File> {
-    let (size_str, name) = line.split_once(' ')?;
+pub fn parse_file(line: &str) -> Result<File, SolveError> {
+    let (size_str, name) = error::split_once(line, " ", "a file size and name")?;
 
-    let size: usize = size_str.parse().ok()?;
+    let size: usize = error::number(size_str)?;
 
-    Some(File {
+    Ok(File {
         name: name.to_string(),
         size,
     })
 }
 
-pub fn parse_dir(current_path: PathBuf, line: &str) -> Option<PathBuf> {
-    let dir_str = line.strip_prefix("dir ")?;
+pub fn parse_dir(current_path: PathBuf, line: &str) -> Result<PathBuf, SolveError> {
+    let dir_str = error::strip_prefix(line, "dir ")?;
+
+    // Names that lead back up the tree would make a directory contain itself
+    if matches!(dir_str, "" | "." | "..") || dir_str.contains('/') {
+        return Err(SolveError::new(dir_str, "a directory name"));
+    }
 
     let mut output = current_path;
     output.push(dir_str);
 
-    Some(output)
+    Ok(output)
 }
 
 pub fn listing_to_directory(listing: &Listing, listings: &Listings) -> Directory {
@@ -91,7 +107,7 @@ pub fn listing_to_directory(listing: &Listing, listings: &Listings) -> Directory
     }
 }
 
-pub fn parse_listings(input: &str) -> Listings {
+pub fn parse_listings(input: &str) -> Result<Listings, SolveError> {
     let mut current_path: PathBuf = PathBuf::from("/");
     let mut listings = Listings::new();
 
@@ -106,7 +122,11 @@ pub fn parse_listings(input: &str) -> Listings {
 
     for line in input.lines() {
         if line.starts_with('$') {
-            let cmd = line.strip_prefix("$ ").unwrap();
+            let cmd = error::strip_prefix(line, "$ ")?;
+
+            if cmd != "ls" && !cmd.starts_with("cd ") {
+                return Err(SolveError::new(cmd, "a `cd` or `ls` command"));
+            }
 
             if let Some(dir) = cmd.strip_prefix("cd ") {
                 if dir.eq("..") {
@@ -129,20 +149,19 @@ pub fn parse_listings(input: &str) -> Listings {
         } else if line.starts_with("dir") {
             let listing = listings.get_mut(&current_path).unwrap();
 
-            let dir_path = parse_dir(current_path.clone(), line).expect("parse dir");
+            let dir_path = parse_dir(current_path.clone(), line)?;
 
             listing.directories.push(dir_path);
         } else {
             let listing = listings.get_mut(&current_path).unwrap();
 
-            let file =
-                parse_file(line).unwrap_or_else(|| panic!("parse file, from line: {}", line));
+            let file = parse_file(line)?;
 
             listing.files.push(file);
         }
     }
 
-    listings
+    Ok(listings)
 }
 
 pub fn directories_within_limit(size_limit: usize, root: &Directory) -> Vec<(PathBuf, usize)> {
@@ -166,52 +185,65 @@ pub fn directories_within_limit(size_limit: usize, root: &Directory) -> Vec<(Pat
     output
 }
 
-impl From<&str> for Directory {
-    fn from(input: &str) -> Self {
-        let listings = parse_listings(input);
+impl TryFrom<&str> for Directory {
+    type Error = SolveError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let listings = parse_listings(input)?;
         let root_listing = listings.get(&PathBuf::from("/")).unwrap();
-        listing_to_directory(root_listing, &listings)
+        Ok(listing_to_directory(root_listing, &listings))
     }
 }
 
-pub fn solve_1(input: &str) -> String {
-    let root = Directory::from(input);
+pub fn parse(input: &str) -> Result<Directory, SolveError> {
+    let root = Directory::try_from(input)?;
 
+    if find_smallest_needed_delete(&root, TOTAL_SPACE, NEEDED_SPACE).is_none() {
+        let end = &input[input.trim_end().len()..];
+        let expected = format!("files of at most {} bytes in total", TOTAL_SPACE);
+        return Err(SolveError::new(end, expected));
+    }
+
+    Ok(root)
+}
+
+pub fn solve_1(root: &Directory) -> Answer {
     let mut sum = 0;
 
-    for (_, size) in directories_within_limit(100000, &root) {
+    for (_, size) in directories_within_limit(100000, root) {
         sum += size;
     }
 
-    sum.to_string()
+    sum.into()
 }
 
 pub fn find_smallest_needed_delete(
-    root: Directory,
+    root: &Directory,
     total_space: usize,
     needed_space: usize,
-) -> usize {
+) -> Option<usize> {
     let mut cache = HashMap::new();
 
     let used_space = root.size(&mut cache);
-    let free_space = total_space - used_space;
-    let target = needed_space - free_space;
+    let free_space = total_space.checked_sub(used_space)?;
+    let target = needed_space.saturating_sub(free_space);
 
     let mut candidates = root.descendants();
 
     // Anything goes!
-    candidates.push(root);
+    candidates.push(root.clone());
 
-    let best = candidates
+    // We only accept the best
+    candidates
         .iter()
         .map(|directory| directory.size(&mut cache))
         .filter(|size| *size >= target)
-        .min();
-
-    // We only accept the best
-    best.expect("the best")
+        .min()
 }
 
-pub fn solve_2(input: &str) -> String {
-    find_smallest_needed_delete(Directory::from(input), 70000000, 30000000).to_string()
+pub fn solve_2(root: &Directory) -> Answer {
+    // Parsing made sure that there is one
+    find_smallest_needed_delete(root, TOTAL_SPACE, NEEDED_SPACE)
+        .unwrap_or_default()
+        .into()
 }
@@ -1,5 +1,13 @@
 use std::ops::{Deref, Mul};
 
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    solver::solution,
+};
+
+solution!(8, "Treetop Tree House");
+
 #[derive(PartialEq, Eq, Hash, Clone)]
 pub struct Coord {
     pub x: usize,
@@ -21,8 +29,10 @@ impl Deref for TreeGrid {
     }
 }
 
-impl From<&str> for TreeGrid {
-    fn from(input: &str) -> Self {
+impl TryFrom<&str> for TreeGrid {
+    type Error = SolveError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
         let mut data = vec![];
 
         let mut width = 0;
@@ -35,8 +45,8 @@ impl From<&str> for TreeGrid {
             }
 
             let mut row = vec![];
-            for char in line.chars() {
-                let h: usize = char.to_string().parse().expect("parse height");
+            for (index, char) in line.char_indices() {
+                let h: usize = error::number(&line[index..index + char.len_utf8()])?;
 
                 row.push(h);
             }
@@ -44,11 +54,11 @@ impl From<&str> for TreeGrid {
             data.push(row);
         }
 
-        Self {
+        Ok(Self {
             inner: data,
             width,
             height,
-        }
+        })
     }
 }
 
@@ -84,8 +94,11 @@ impl TreeGrid {
     }
 }
 
-pub fn solve_1(input: &str) -> String {
-    let scan = TreeGrid::from(input);
+pub fn parse(input: &str) -> Result<TreeGrid, SolveError> {
+    TreeGrid::try_from(input)
+}
+
+pub fn solve_1(scan: &TreeGrid) -> Answer {
     let width = scan.width;
     let height = scan.height;
 
@@ -99,7 +112,7 @@ pub fn solve_1(input: &str) -> String {
         }
     }
 
-    visible.to_string()
+    visible.into()
 }
 
 pub enum ViewDirection {
@@ -158,9 +171,7 @@ impl TreeGrid {
     }
 }
 
-pub fn solve_2(input: &str) -> String {
-    let scan = TreeGrid::from(input);
-
+pub fn solve_2(scan: &TreeGrid) -> Answer {
     let mut max = 0;
 
     let width = scan.width;
@@ -176,5 +187,18 @@ pub fn solve_2(input: &str) -> String {
         }
     }
 
-    max.to_string()
+    max.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Coord, TreeGrid};
+
+    #[test]
+    fn scenic_scores() {
+        let scan = TreeGrid::try_from(include_str!("../example_input/day8.txt")).unwrap();
+
+        assert_eq!(scan.scenic_score(&Coord { x: 2, y: 1 }), 4);
+        assert_eq!(scan.scenic_score(&Coord { x: 0, y: 0 }), 0);
+    }
 }
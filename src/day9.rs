@@ -1,6 +1,14 @@
-use std::{collections::HashSet, fmt::Display, iter::repeat};
+use std::{collections::HashSet, fmt::Display, iter::repeat_n};
 
-use crate::extra::visualize;
+use crate::{
+    answers::Answer,
+    error::{self, SolveError},
+    extra::{visualize, ChristmasGraph, Color, GraphMetadata, Style},
+    record::Recorder,
+    solver::solution,
+};
+
+solution!(9, "Rope Bridge");
 
 #[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
 pub struct Position {
@@ -65,14 +73,16 @@ pub enum Direction {
     L,
 }
 
-impl From<&str> for Direction {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for Direction {
+    type Error = SolveError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "R" => Direction::R,
-            "U" => Direction::U,
-            "L" => Direction::L,
-            "D" => Direction::D,
-            _ => unreachable!(),
+            "R" => Ok(Direction::R),
+            "U" => Ok(Direction::U),
+            "L" => Ok(Direction::L),
+            "D" => Ok(Direction::D),
+            _ => Err(SolveError::new(value, "one of `U`, `R`, `D` or `L`")),
         }
     }
 }
@@ -92,7 +102,7 @@ impl RopeSimulation {
 
         output
             .parts
-            .append(&mut repeat(Position::default()).take(size).collect());
+            .append(&mut repeat_n(Position::default(), size).collect());
 
         output
     }
@@ -102,8 +112,8 @@ impl RopeSimulation {
             .insert(self.parts.last().expect("last tail part").clone());
     }
 
-    pub fn perform_move(&mut self, instruction: Direction) {
-        self.parts[0].move_direction(&instruction);
+    pub fn perform_move(&mut self, instruction: &Direction) {
+        self.parts[0].move_direction(instruction);
         let head = self.parts[0].clone();
         let mut cursor = self.parts[0].clone();
 
@@ -119,17 +129,78 @@ impl RopeSimulation {
         self.bounding_box.upper.y = self.bounding_box.upper.x.max(head.y);
     }
 
-    pub fn perform_moves(&mut self, moves: Vec<Direction>) -> usize {
+    /// Moves the rope, recording it after every step of the head, and
+    /// returns the number of positions its tail visited.
+    pub fn perform_moves(&mut self, moves: &[Direction], recorder: &mut Recorder) -> usize {
         self.mark_tail();
+        recorder.record(self);
 
         for instruction in moves {
             self.perform_move(instruction);
+            recorder.record(self);
         }
 
         self.tail_markers.len()
     }
 }
 
+impl RopeSimulation {
+    /// The knot at `point`, or `#` if the tail has been there.
+    fn cell(&self, point: &Position) -> Option<char> {
+        match self.parts.iter().position(|part| part == point) {
+            Some(0) => Some('H'),
+            Some(1) if self.parts.len() == 2 => Some('T'),
+            Some(index) => char::from_digit(index as u32, 36),
+            None => self.tail_markers.contains(point).then_some('#'),
+        }
+    }
+}
+
+impl ChristmasGraph for RopeSimulation {
+    fn as_graph_metadata(&self) -> GraphMetadata {
+        let points = || self.parts.iter().chain(&self.tail_markers);
+
+        let min_x = points().map(|point| point.x).min().unwrap_or(0);
+        let min_y = points().map(|point| point.y).min().unwrap_or(0);
+        let max_x = points().map(|point| point.x).max().unwrap_or(0);
+        let max_y = points().map(|point| point.y).max().unwrap_or(0);
+
+        let legend_y_width = min_y.to_string().len().max(max_y.to_string().len()) as u32;
+        let legend_x_width = min_x.to_string().len().max(max_x.to_string().len()) as u32;
+
+        GraphMetadata {
+            x: min_x,
+            y: min_y,
+            width: (max_x - min_x + 1) as u32,
+            height: (max_y - min_y + 1) as u32,
+            legend_step_x: 5,
+            legend_step_y: 1,
+            legend_y_width,
+            legend_x_width,
+        }
+    }
+
+    fn graph_legend_x(&self, value: i64) -> Vec<char> {
+        value.to_string().chars().collect()
+    }
+
+    fn graph_legend_y(&self, value: i64) -> Vec<char> {
+        value.to_string().chars().collect()
+    }
+
+    fn graph_value(&self, x: i64, y: i64) -> Option<char> {
+        self.cell(&Position { x, y })
+    }
+
+    fn graph_style(&self, x: i64, y: i64) -> Style {
+        match self.cell(&Position { x, y }) {
+            Some('H') => Style::color(Color::Red).bold(),
+            Some('#') => Style::DIM,
+            _ => Style::color(Color::Yellow),
+        }
+    }
+}
+
 impl Display for RopeSimulation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let bounding_box = self.bounding_box.clone();
@@ -138,26 +209,7 @@ impl Display for RopeSimulation {
             for x in bounding_box.lower.x..=bounding_box.upper.x {
                 let point = Position { x, y };
 
-                let char = self
-                    .parts
-                    .iter()
-                    .enumerate()
-                    .find(|(_, part)| part.eq(&&point))
-                    .map(|(index, _)| match index {
-                        0 => "H".to_string(),
-                        1 => (if self.parts.len() > 2 { "1" } else { "T" }).to_string(),
-                        i => format!("{}", i),
-                    })
-                    .unwrap_or_else(|| {
-                        (if self.tail_markers.contains(&point) {
-                            "#"
-                        } else {
-                            "."
-                        })
-                        .to_string()
-                    });
-
-                write!(f, "{}", char)?;
+                write!(f, "{}", self.cell(&point).unwrap_or('.'))?;
             }
             writeln!(f)?;
         }
@@ -166,37 +218,41 @@ impl Display for RopeSimulation {
     }
 }
 
-pub fn parse_directions(input: &str) -> Vec<Direction> {
+pub fn parse(input: &str) -> Result<Vec<Direction>, SolveError> {
     let mut output = vec![];
 
     for line in input.lines() {
-        let (dir_str, count_str) = line.split_once(' ').unwrap();
-        let dir = Direction::from(dir_str);
-        let count: usize = count_str.parse().unwrap();
+        let (dir_str, count_str) = error::split_once(line, " ", "a direction and a step count")?;
+        let dir = Direction::try_from(dir_str)?;
+        let count: usize = error::number(count_str)?;
 
-        let mut moves: Vec<Direction> = repeat(dir).take(count).collect();
+        let mut moves: Vec<Direction> = repeat_n(dir, count).collect();
         output.append(&mut moves)
     }
 
-    output
+    Ok(output)
 }
 
-pub fn solve_1(input: &str) -> String {
+pub fn solve_1(directions: &[Direction]) -> Answer {
     let mut simulation = RopeSimulation::new(2);
+    let mut recorder = Recorder::new(9, 1);
 
-    let moves = simulation.perform_moves(parse_directions(input));
+    let moves = simulation.perform_moves(directions, &mut recorder);
+    recorder.finish(&simulation);
 
     visualize(9, 1, &simulation);
 
-    moves.to_string()
+    moves.into()
 }
 
-pub fn solve_2(input: &str) -> String {
+pub fn solve_2(directions: &[Direction]) -> Answer {
     let mut simulation = RopeSimulation::new(10);
+    let mut recorder = Recorder::new(9, 2);
 
-    let moves = simulation.perform_moves(parse_directions(input));
+    let moves = simulation.perform_moves(directions, &mut recorder);
+    recorder.finish(&simulation);
 
     visualize(9, 2, &simulation);
 
-    moves.to_string()
+    moves.into()
 }
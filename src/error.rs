@@ -0,0 +1,158 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// Malformed puzzle input, pointing at the offending text.
+///
+/// Parsers create the error from a slice of their input with [`SolveError::new`],
+/// the day and position are filled in by [`SolveError::locate`] once the whole
+/// input is known. Line and column are 1-based and stay 0 for text that is not
+/// part of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+    /// The whole input line containing the text, for diagnostics.
+    pub source: String,
+    /// Where the text started in memory, only compared against the input.
+    address: usize,
+}
+
+impl SolveError {
+    pub fn new(text: &str, expected: impl Into<String>) -> Self {
+        Self {
+            day: 0,
+            line: 0,
+            column: 0,
+            text: text.to_string(),
+            expected: expected.into(),
+            source: String::new(),
+            address: text.as_ptr() as usize,
+        }
+    }
+
+    /// Finds the line and column of the text within `input`.
+    pub fn locate(mut self, day: u8, input: &str) -> Self {
+        self.day = day;
+
+        let start = input.as_ptr() as usize;
+        let Some(offset) = self
+            .address
+            .checked_sub(start)
+            .filter(|offset| *offset <= input.len() && input.is_char_boundary(*offset))
+        else {
+            return self;
+        };
+
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+        self.line = before.matches('\n').count() + 1;
+        self.column = before[line_start..].chars().count() + 1;
+        self.source = input[line_start..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        self
+    }
+
+    /// The error followed by the input line, marking the offending text.
+    pub fn diagnostic(&self) -> String {
+        if self.line == 0 {
+            return format!("{}\n", self);
+        }
+
+        let number = self.line.to_string();
+        let padding = " ".repeat(number.len());
+        let width = self
+            .text
+            .lines()
+            .next()
+            .map_or(0, |text| text.chars().count())
+            .max(1);
+
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}{}\n",
+            self,
+            padding,
+            number,
+            self.source,
+            padding,
+            " ".repeat(self.column - 1),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = match (self.day, self.line) {
+            (0, 0) => String::new(),
+            (0, line) => format!("line {}, column {}: ", line, self.column),
+            (day, 0) => format!("day {}: ", day),
+            (day, line) => format!("day {}, line {}, column {}: ", day, line, self.column),
+        };
+
+        f.write_str(&location)?;
+
+        match self.text.is_empty() {
+            true => write!(f, "expected {}, found nothing", self.expected),
+            false => write!(f, "expected {}, found `{}`", self.expected, self.text),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+pub fn split_once<'a>(
+    text: &'a str,
+    delimiter: &str,
+    expected: &str,
+) -> Result<(&'a str, &'a str), SolveError> {
+    text.split_once(delimiter)
+        .ok_or_else(|| SolveError::new(text, expected))
+}
+
+pub fn strip_prefix<'a>(text: &'a str, prefix: &str) -> Result<&'a str, SolveError> {
+    text.strip_prefix(prefix)
+        .ok_or_else(|| SolveError::new(text, format!("`{}`", prefix.trim())))
+}
+
+pub fn number<T: FromStr>(text: &str) -> Result<T, SolveError> {
+    text.parse().map_err(|_| SolveError::new(text, "a number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{number, split_once, SolveError};
+
+    #[test]
+    fn errors_are_located_in_the_input() {
+        let input = "2-4,6-8\n2-x,6-8\n";
+        let line = input.lines().nth(1).unwrap();
+        let (left, _) = split_once(line, ",", "two section assignments").unwrap();
+
+        let error = number::<u32>(&left[2..]).unwrap_err().locate(4, input);
+
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(
+            error.to_string(),
+            "day 4, line 2, column 3: expected a number, found `x`"
+        );
+        assert_eq!(
+            error.diagnostic(),
+            "day 4, line 2, column 3: expected a number, found `x`\n  |\n2 | 2-x,6-8\n  |   ^\n"
+        );
+    }
+
+    #[test]
+    fn errors_outside_the_input_are_not_located() {
+        let text = String::from("7");
+        let error = SolveError::new(&text, "a letter").locate(2, "A Y\n");
+
+        assert_eq!((error.line, error.column), (0, 0));
+        assert_eq!(error.to_string(), "day 2: expected a letter, found `7`");
+    }
+}
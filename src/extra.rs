@@ -1,6 +1,101 @@
-use std::{env, fmt::Display, path::PathBuf};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    env,
+    fmt::{self, Display},
+    fs::{self, OpenOptions},
+    io::{IsTerminal, Write},
+    mem,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
 
-use crate::day7::Directory;
+use crate::{
+    answers::Grid,
+    day7::Directory,
+    image::{Image, Palette, Rgb},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    /// The color as the usual terminal themes show it.
+    pub fn rgb(self) -> Rgb {
+        match self {
+            Color::Black => [0, 0, 0],
+            Color::Red => [205, 49, 49],
+            Color::Green => [13, 188, 121],
+            Color::Yellow => [229, 229, 16],
+            Color::Blue => [36, 114, 200],
+            Color::Magenta => [188, 63, 188],
+            Color::Cyan => [17, 168, 205],
+            Color::White => [229, 229, 229],
+        }
+    }
+}
+
+/// How a cell of a [`ChristmasGraph`] is drawn on a terminal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+}
+
+impl Style {
+    pub const DIM: Style = Style {
+        color: None,
+        bold: false,
+        dim: true,
+    };
+
+    pub fn color(color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..Self::default()
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// Writes `text` in this style when the formatter asks for colors with
+    /// `{:#}`, and plain otherwise.
+    pub fn paint(&self, f: &mut fmt::Formatter<'_>, text: impl Display) -> fmt::Result {
+        if !f.alternate() || *self == Style::default() {
+            return write!(f, "{}", text);
+        }
+
+        let mut codes = vec![];
+
+        if self.bold {
+            codes.push(1);
+        }
+
+        if self.dim {
+            codes.push(2);
+        }
+
+        if let Some(color) = self.color {
+            codes.push(30 + color as u8);
+        }
+
+        let codes: Vec<String> = codes.iter().map(u8::to_string).collect();
+
+        write!(f, "\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
 
 pub struct GraphMetadata {
     pub x: i64,
@@ -18,28 +113,66 @@ pub trait ChristmasGraph {
     fn graph_legend_x(&self, value: i64) -> Vec<char>;
     fn graph_legend_y(&self, value: i64) -> Vec<char>;
     fn graph_value(&self, x: i64, y: i64) -> Option<char>;
+
+    /// The style of a cell, which only shows when rendered with `{:#}`.
+    fn graph_style(&self, _x: i64, _y: i64) -> Style {
+        Style::default()
+    }
+
+    /// Draws the graph through the viewport configured for visualizations,
+    /// in color when the formatter asks for it with `{:#}`.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.render(f, viewport())
+    }
+
+    /// Draws the part of the graph within `viewport`, one character per
+    /// block of cells when it has to be scaled down, with legends at the
+    /// multiples of the legend steps that fit the scale.
+    fn render(&self, f: &mut std::fmt::Formatter<'_>, viewport: &Viewport) -> std::fmt::Result {
         let graph_metadata = self.as_graph_metadata();
+        let (graph_x, graph_y, width, height) = viewport.window(self, &graph_metadata);
+
+        // Blocks of `scale` by `scale` cells are drawn as one
+        let scale = viewport.scale(&graph_metadata, width, height);
+        let columns = width.div_ceil(scale);
+        let rows = height.div_ceil(scale);
+        let step_x = graph_metadata.legend_step_x.max(1) as i64 * scale as i64;
+        let step_y = graph_metadata.legend_step_y.max(1) as i64 * scale as i64;
+
+        let block_x = |column: u32| graph_x + (column * scale) as i64;
+        let block_y = |row: u32| graph_y + (row * scale) as i64;
 
-        let graph_x = graph_metadata.x;
-        let graph_y = graph_metadata.y;
-        let width = graph_metadata.width;
-        let height = graph_metadata.height;
-        let end_x = graph_x + width as i64;
-        let end_y = graph_y + height as i64;
-        let step_x = graph_metadata.legend_step_x;
-        let step_y = graph_metadata.legend_step_y;
-
-        let legend_x_width = graph_metadata.legend_x_width;
-        let legend_y_width = graph_metadata.legend_y_width;
-
-        for y in 0..legend_x_width as usize {
-            write!(f, "{:>width$}", "", width = legend_y_width as usize + 1)?;
-            for x in graph_x..end_x {
-                if x % step_x as i64 == 0 {
-                    let legend = *self.graph_legend_x(x).get(y).unwrap_or(&' ');
-                    write!(f, "{} ", legend)?;
-                } else if x + 1 - graph_x != width as i64 {
+        // The multiple of `step` within the block starting at `start`
+        let legend = |start: i64, step: i64| {
+            let multiple = (start + scale as i64 - 1).div_euclid(step) * step;
+            (multiple >= start).then_some(multiple)
+        };
+
+        let legends_x: Vec<Option<Vec<char>>> = (0..columns)
+            .map(|column| legend(block_x(column), step_x).map(|x| self.graph_legend_x(x)))
+            .collect();
+        let legends_y: Vec<Option<Vec<char>>> = (0..rows)
+            .map(|row| legend(block_y(row), step_y).map(|y| self.graph_legend_y(y)))
+            .collect();
+
+        let legend_x_width = legends_x
+            .iter()
+            .flatten()
+            .map(Vec::len)
+            .fold(graph_metadata.legend_x_width as usize, usize::max);
+        let legend_y_width = legends_y
+            .iter()
+            .flatten()
+            .map(Vec::len)
+            .fold(graph_metadata.legend_y_width as usize, usize::max);
+
+        for y in 0..legend_x_width {
+            write!(f, "{:>width$}", "", width = legend_y_width + 1)?;
+            for (column, legend) in legends_x.iter().enumerate() {
+                if let Some(legend) = legend {
+                    Style::DIM.paint(f, *legend.get(y).unwrap_or(&' '))?;
+                    write!(f, " ")?;
+                } else if column as u32 + 1 != columns {
                     write!(f, "  ")?;
                 }
             }
@@ -47,37 +180,319 @@ pub trait ChristmasGraph {
             writeln!(f)?;
         }
 
-        for y in graph_y..end_y {
-            if y % step_y as i64 == 0 {
-                let legend = self.graph_legend_y(y);
+        for (row, legend) in legends_y.iter().enumerate() {
+            let legend: String = legend.iter().flatten().collect();
+            write!(
+                f,
+                "{:>width$}",
+                "",
+                width = legend_y_width - legend.chars().count()
+            )?;
+            if !legend.is_empty() {
+                Style::DIM.paint(f, legend)?;
+            }
+            write!(f, " ")?;
 
-                for _ in 0..(legend_y_width as usize - legend.len()) {
+            for column in 0..columns {
+                match self.block_value(block_x(column), block_y(row as u32), scale) {
+                    Some((value, x, y)) => self.graph_style(x, y).paint(f, value)?,
+                    None => write!(f, ".")?,
+                }
+                if column + 1 != columns {
                     write!(f, " ")?;
                 }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
 
-                for char in legend {
-                    char.fmt(f)?;
+    /// The most common value among the cells of a block, with the cell it
+    /// was first seen at for its style.
+    fn block_value(&self, x: i64, y: i64, scale: u32) -> Option<(char, i64, i64)> {
+        let mut counts: Vec<(char, i64, i64, usize)> = vec![];
+
+        for cell_y in y..y + scale as i64 {
+            for cell_x in x..x + scale as i64 {
+                let Some(value) = self.graph_value(cell_x, cell_y) else {
+                    continue;
+                };
+
+                match counts.iter_mut().find(|(seen, ..)| *seen == value) {
+                    Some((.., count)) => *count += 1,
+                    None => counts.push((value, cell_x, cell_y, 1)),
                 }
-            } else {
-                write!(f, "{:>width$}", "", width = legend_y_width as usize)?;
             }
+        }
 
-            write!(f, " ")?;
+        // The first of the most common values wins ties
+        counts
+            .into_iter()
+            .rev()
+            .max_by_key(|(.., count)| *count)
+            .map(|(value, x, y, _)| (value, x, y))
+    }
 
-            for x in graph_x..end_x {
-                if let Some(value) = self.graph_value(x, y) {
-                    value.fmt(f)?;
-                } else {
-                    write!(f, ".")?;
-                }
-                if x + 1 - graph_x != width as i64 {
-                    write!(f, " ")?;
+    /// Draws every cell of the graph as a square of `scale` pixels, in the
+    /// color `palette` has for its value, or else the color of its style.
+    fn to_image(&self, palette: &Palette, scale: u32) -> Image {
+        let graph_metadata = self.as_graph_metadata();
+        let scale = scale.max(1);
+        let mut image = Image::new(
+            graph_metadata.width * scale,
+            graph_metadata.height * scale,
+            palette.background,
+        );
+
+        for row in 0..graph_metadata.height {
+            for column in 0..graph_metadata.width {
+                let x = graph_metadata.x + column as i64;
+                let y = graph_metadata.y + row as i64;
+
+                let Some(value) = self.graph_value(x, y) else {
+                    continue;
+                };
+
+                let color = palette
+                    .get(value)
+                    .or_else(|| self.graph_style(x, y).color.map(Color::rgb))
+                    .unwrap_or(palette.foreground);
+
+                image.fill(column * scale, row * scale, scale, color);
+            }
+        }
+
+        image
+    }
+
+    /// Draws the graph through `viewport` instead of the configured one.
+    fn view(&self, viewport: Viewport) -> View<'_, Self>
+    where
+        Self: Sized,
+    {
+        View {
+            graph: self,
+            viewport,
+        }
+    }
+}
+
+/// The part of a [`ChristmasGraph`] that is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Window {
+    /// Everything the graph covers.
+    #[default]
+    Full,
+    /// The smallest rectangle holding every non-empty cell.
+    Crop,
+    /// The cells from `x` and `y` on, for `width` by `height` cells.
+    Area {
+        x: i64,
+        y: i64,
+        width: u32,
+        height: u32,
+    },
+}
+
+/// How a [`ChristmasGraph`] is drawn: which part of it, and the number of
+/// columns and rows of characters it is scaled down to fit in, if any.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Viewport {
+    pub window: Window,
+    pub fit: Option<(u32, u32)>,
+}
+
+impl Viewport {
+    /// Reads the viewport of visualizations from `VISUALIZE_VIEW`, which is
+    /// `full`, `crop` or an area like `480..520,0..12`, and `VISUALIZE_FIT`,
+    /// which is a size like `120x40` or `auto` for the terminal size.
+    pub fn from_env() -> Result<Self, String> {
+        let window = match env::var("VISUALIZE_VIEW") {
+            Ok(view) => Window::parse(&view)?,
+            Err(_) => Window::Full,
+        };
+
+        let fit = match env::var("VISUALIZE_FIT").as_deref() {
+            Ok("auto") => Some(terminal_size()),
+            Ok(fit) => Some(parse_size(fit)?),
+            Err(_) => None,
+        };
+
+        Ok(Self { window, fit })
+    }
+
+    /// The cells to draw, as `x`, `y`, `width` and `height`.
+    fn window<G: ChristmasGraph + ?Sized>(
+        &self,
+        graph: &G,
+        metadata: &GraphMetadata,
+    ) -> (i64, i64, u32, u32) {
+        let full = (metadata.x, metadata.y, metadata.width, metadata.height);
+
+        match self.window {
+            Window::Full => full,
+            Window::Area {
+                x,
+                y,
+                width,
+                height,
+            } => (x, y, width, height),
+            Window::Crop => {
+                let cells: Vec<(i64, i64)> = (metadata.y..metadata.y + metadata.height as i64)
+                    .flat_map(|y| {
+                        (metadata.x..metadata.x + metadata.width as i64).map(move |x| (x, y))
+                    })
+                    .filter(|(x, y)| graph.graph_value(*x, *y).is_some())
+                    .collect();
+
+                let xs = cells.iter().map(|(x, _)| *x);
+                let ys = cells.iter().map(|(_, y)| *y);
+
+                match (xs.clone().min(), xs.max(), ys.clone().min(), ys.max()) {
+                    (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) => (
+                        min_x,
+                        min_y,
+                        (max_x - min_x + 1) as u32,
+                        (max_y - min_y + 1) as u32,
+                    ),
+                    _ => full,
                 }
             }
-            writeln!(f)?;
         }
+    }
 
-        Ok(())
+    /// The smallest number of cells per side of a block that fits the
+    /// window into the target size, with two characters per column.
+    fn scale(&self, metadata: &GraphMetadata, width: u32, height: u32) -> u32 {
+        let Some((columns, rows)) = self.fit else {
+            return 1;
+        };
+
+        let columns = (columns.saturating_sub(metadata.legend_y_width) / 2).max(1);
+        let rows = rows.saturating_sub(metadata.legend_x_width).max(1);
+
+        width.div_ceil(columns).max(height.div_ceil(rows)).max(1)
+    }
+}
+
+impl Window {
+    fn parse(view: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "invalid view `{}`, expected `full`, `crop` or an area like `480..520,0..12`",
+                view
+            )
+        };
+
+        let range = |range: &str| {
+            let (from, to) = range.split_once("..").ok_or_else(invalid)?;
+            let from: i64 = from.trim().parse().map_err(|_| invalid())?;
+            let to: i64 = to.trim().parse().map_err(|_| invalid())?;
+
+            match to > from {
+                true => Ok((from, (to - from) as u32)),
+                false => Err(invalid()),
+            }
+        };
+
+        match view {
+            "full" => Ok(Window::Full),
+            "crop" => Ok(Window::Crop),
+            _ => {
+                let (xs, ys) = view.split_once(',').ok_or_else(invalid)?;
+                let (x, width) = range(xs)?;
+                let (y, height) = range(ys)?;
+
+                Ok(Window::Area {
+                    x,
+                    y,
+                    width,
+                    height,
+                })
+            }
+        }
+    }
+}
+
+fn parse_size(size: &str) -> Result<(u32, u32), String> {
+    size.split_once('x')
+        .and_then(|(columns, rows)| Some((columns.parse().ok()?, rows.parse().ok()?)))
+        .filter(|(columns, rows)| *columns > 0 && *rows > 0)
+        .ok_or_else(|| {
+            format!(
+                "invalid fit `{}`, expected `auto` or a size like `120x40`",
+                size
+            )
+        })
+}
+
+/// The size of the terminal as the shell exports it, or the classic 80x24.
+fn terminal_size() -> (u32, u32) {
+    let dimension = |name: &str, default: u32| {
+        env::var(name)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+    };
+
+    (dimension("COLUMNS", 80), dimension("LINES", 24))
+}
+
+/// The viewport of visualizations, read from the environment once. Invalid
+/// settings are reported and the whole graph is drawn instead.
+pub fn viewport() -> &'static Viewport {
+    static VIEWPORT: OnceLock<Viewport> = OnceLock::new();
+
+    VIEWPORT.get_or_init(|| {
+        Viewport::from_env().unwrap_or_else(|error| {
+            eprintln!("warning: {}", error);
+            Viewport::default()
+        })
+    })
+}
+
+/// A graph drawn through a viewport of its own, see [`ChristmasGraph::view`].
+pub struct View<'a, G> {
+    graph: &'a G,
+    viewport: Viewport,
+}
+
+impl<G: ChristmasGraph> Display for View<'_, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.graph.render(f, &self.viewport)
+    }
+}
+
+/// Lit pixels are drawn as `#`, so answers like the day 10 screen can be
+/// turned into images.
+impl ChristmasGraph for Grid {
+    fn as_graph_metadata(&self) -> GraphMetadata {
+        GraphMetadata {
+            x: 0,
+            y: 0,
+            width: self.width as u32,
+            height: self.pixels.len().checked_div(self.width).unwrap_or(0) as u32,
+            legend_step_x: 5,
+            legend_step_y: 1,
+            legend_y_width: 1,
+            legend_x_width: 2,
+        }
+    }
+
+    fn graph_legend_x(&self, value: i64) -> Vec<char> {
+        value.to_string().chars().collect()
+    }
+
+    fn graph_legend_y(&self, value: i64) -> Vec<char> {
+        value.to_string().chars().collect()
+    }
+
+    fn graph_value(&self, x: i64, y: i64) -> Option<char> {
+        let x = usize::try_from(x).ok().filter(|x| *x < self.width)?;
+        let y = usize::try_from(y).ok()?;
+
+        (*self.pixels.get(y * self.width + x)?).then_some('#')
     }
 }
 
@@ -109,46 +524,379 @@ impl Display for Directory {
     }
 }
 
+/// Whether visualizations written to `stream` are drawn in color, which
+/// they are on a terminal unless `NO_COLOR` is set to anything but an empty
+/// string.
+pub fn colors(stream: impl IsTerminal) -> bool {
+    let disabled = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+    !disabled && stream.is_terminal()
+}
+
+/// Where the drawings of [`visualize`] go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sink {
+    /// Printed as soon as they are drawn.
+    Stdout,
+    /// Written to a `dayN-partM.txt` file per part in the directory.
+    Files(PathBuf),
+    /// Kept until they are taken with [`Visualizer::take`].
+    Buffer,
+    /// Thrown away without being drawn.
+    Null,
+}
+
+/// What one part drew with [`visualize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drawing {
+    pub day: u8,
+    pub part: u8,
+    pub text: String,
+}
+
+/// Shows the drawing under a header naming its day and part.
+impl Display for Drawing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title = format!(" Day {} part {} ", self.day, self.part);
+
+        writeln!(f, "{:-^80}", title)?;
+        write!(f, "{}", self.text)?;
+
+        match self.text.ends_with('\n') {
+            true => Ok(()),
+            false => writeln!(f),
+        }
+    }
+}
+
+/// Sends the drawings of the solvers to a [`Sink`]. Every drawing is
+/// labelled with its day and part, so the ones drawn by solvers running in
+/// parallel can be told apart and put in order.
+pub struct Visualizer {
+    sink: Sink,
+    colors: bool,
+    drawings: Mutex<Vec<Drawing>>,
+    /// The files drawn to so far, which are started over on their first
+    /// drawing.
+    files: Mutex<HashSet<PathBuf>>,
+}
+
+impl Visualizer {
+    pub fn new(sink: Sink) -> Self {
+        Self {
+            sink,
+            colors: false,
+            drawings: Mutex::new(vec![]),
+            files: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Draws in color for the sinks that end up on a terminal, which files
+    /// never do.
+    pub fn with_colors(self, colors: bool) -> Self {
+        Self { colors, ..self }
+    }
+
+    pub fn draw(&self, day: u8, part: u8, value: &dyn Display) {
+        let text = match (&self.sink, self.colors) {
+            (Sink::Null, _) => return,
+            (Sink::Stdout | Sink::Buffer, true) => format!("{:#}", value),
+            _ => value.to_string(),
+        };
+
+        match &self.sink {
+            Sink::Stdout => println!("{}", text),
+            Sink::Buffer => self
+                .drawings
+                .lock()
+                .unwrap()
+                .push(Drawing { day, part, text }),
+            Sink::Files(directory) => {
+                if let Err(error) = self.write(directory, day, part, &text) {
+                    eprintln!(
+                        "warning: cannot visualize day {} part {}: {}",
+                        day, part, error
+                    );
+                }
+            }
+            Sink::Null => {}
+        }
+    }
+
+    fn write(&self, directory: &Path, day: u8, part: u8, text: &str) -> Result<(), String> {
+        let path = directory.join(format!("day{}-part{}.txt", day, part));
+        let first = self.files.lock().unwrap().insert(path.clone());
+
+        fs::create_dir_all(directory)
+            .map_err(|error| format!("cannot create {}: {}", directory.display(), error))?;
+
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(!first)
+            .truncate(first)
+            .open(&path)
+            .and_then(|mut file| writeln!(file, "{}", text))
+            .map_err(|error| format!("cannot write {}: {}", path.display(), error))
+    }
+
+    /// The drawings kept so far, ordered by day and part and then in the
+    /// order they were drawn in.
+    pub fn take(&self) -> Vec<Drawing> {
+        let mut drawings = mem::take(&mut *self.drawings.lock().unwrap());
+        drawings.sort_by_key(|drawing| (drawing.day, drawing.part));
+        drawings
+    }
+}
+
+static VISUALIZER: OnceLock<Visualizer> = OnceLock::new();
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Vec<Drawing>>> = const { RefCell::new(None) };
+}
+
+/// Sends the drawings of every solver to `visualizer`, which is set once,
+/// before anything is solved. Until then nothing is drawn.
+pub fn use_visualizer(visualizer: Visualizer) {
+    if VISUALIZER.set(visualizer).is_err() {
+        panic!("visualizer set once");
+    }
+}
+
+pub fn visualizer() -> Option<&'static Visualizer> {
+    VISUALIZER.get()
+}
+
+/// Calls `call`, keeping what it draws on this thread instead of sending it
+/// to the visualizer, without colors.
+pub fn capture<T>(call: impl FnOnce() -> T) -> (T, Vec<Drawing>) {
+    let outer = CAPTURE.replace(Some(vec![]));
+    let output = call();
+    let drawings = CAPTURE.replace(outer).unwrap_or_default();
+
+    (output, drawings)
+}
+
+/// Draws `value` for `day` and `part`, to the drawings captured on this
+/// thread if any, and to the configured visualizer otherwise.
 pub fn visualize<T>(day: u8, part: u8, value: &T)
 where
     T: Display,
 {
-    fn perform_print<T>(_day: u8, _part: u8, value: &T)
-    where
-        T: Display,
-    {
-        // let line = format!("Day {day:} part {part:}");
-        // let line = format!("{line:-^80}");
-        // println!("{line:}");
-        println!("{value:}")
+    let captured = CAPTURE.with_borrow_mut(|drawings| {
+        let drawings = drawings.as_mut()?;
+        let text = value.to_string();
+
+        drawings.push(Drawing { day, part, text });
+        Some(())
+    });
+
+    if captured.is_some() {
+        return;
     }
 
-    if let Ok(visualize) = env::var("VISUALIZE") {
-        if visualize != "true" && visualize != "1" {
-            return;
-        }
+    if let Some(visualizer) = VISUALIZER.get() {
+        visualizer.draw(day, part, value);
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::{self, Display};
+
+    use super::{
+        capture, parse_size, visualize, ChristmasGraph, Color, Drawing, GraphMetadata, Sink, Style,
+        Viewport, Visualizer, Window,
+    };
+    use crate::{answers::Grid, image::Palette};
+
+    struct Dot;
 
-    match env::var("DAY") {
-        Ok(day_str) => {
-            if day_str.parse::<u8>().unwrap() != day {
-                return;
+    impl ChristmasGraph for Dot {
+        fn as_graph_metadata(&self) -> GraphMetadata {
+            GraphMetadata {
+                x: 0,
+                y: 0,
+                width: 2,
+                height: 1,
+                legend_step_x: 1,
+                legend_step_y: 1,
+                legend_y_width: 1,
+                legend_x_width: 1,
             }
+        }
 
-            match env::var("PART") {
-                Ok(part_str) => {
-                    if part_str.parse::<u8>().unwrap() != part {
-                        return;
-                    }
-                    perform_print(day, part, value)
-                }
-                Err(_) => perform_print(day, part, value),
+        fn graph_legend_x(&self, value: i64) -> Vec<char> {
+            value.to_string().chars().collect()
+        }
+
+        fn graph_legend_y(&self, value: i64) -> Vec<char> {
+            value.to_string().chars().collect()
+        }
+
+        fn graph_value(&self, x: i64, _y: i64) -> Option<char> {
+            (x == 1).then_some('#')
+        }
+
+        fn graph_style(&self, _x: i64, _y: i64) -> Style {
+            Style::color(Color::Red).bold()
+        }
+    }
+
+    impl Display for Dot {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            ChristmasGraph::fmt(self, f)
+        }
+    }
+
+    #[test]
+    fn colors_only_when_asked_for() {
+        assert_eq!(format!("{}", Dot), "  0 1  \n0 . #\n");
+        assert_eq!(
+            format!("{:#}", Dot),
+            "  \x1b[2m0\x1b[0m \x1b[2m1\x1b[0m  \n\x1b[2m0\x1b[0m . \x1b[1;31m#\x1b[0m\n"
+        );
+    }
+
+    /// A floor of rock from 0 to 7 on row 3, with sand resting on 4 and 5.
+    struct Floor;
+
+    impl ChristmasGraph for Floor {
+        fn as_graph_metadata(&self) -> GraphMetadata {
+            GraphMetadata {
+                x: 0,
+                y: 0,
+                width: 8,
+                height: 4,
+                legend_step_x: 2,
+                legend_step_y: 1,
+                legend_y_width: 1,
+                legend_x_width: 1,
             }
         }
-        Err(_) => {
-            if cfg!(test) {
-                perform_print(day, part, value)
+
+        fn graph_legend_x(&self, value: i64) -> Vec<char> {
+            value.to_string().chars().collect()
+        }
+
+        fn graph_legend_y(&self, value: i64) -> Vec<char> {
+            value.to_string().chars().collect()
+        }
+
+        fn graph_value(&self, x: i64, y: i64) -> Option<char> {
+            match (x, y) {
+                (_, 3) => Some('#'),
+                (4 | 5, 2) => Some('o'),
+                _ => None,
             }
         }
     }
+
+    #[test]
+    fn renders_viewports() {
+        let crop = Viewport {
+            window: Window::Crop,
+            fit: None,
+        };
+        assert_eq!(
+            Floor.view(crop).to_string(),
+            "  0   2   4   6  \n2 . . . . o o . .\n3 # # # # # # # #\n"
+        );
+
+        let area = Viewport {
+            window: Window::parse("3..6,2..4").unwrap(),
+            fit: None,
+        };
+        assert_eq!(Floor.view(area).to_string(), "    4  \n2 . o o\n3 # # #\n");
+
+        // Blocks of two by two cells, with the legend steps doubled and the
+        // sand winning its tie with the rock below
+        let fit = Viewport {
+            window: Window::Full,
+            fit: Some((10, 3)),
+        };
+        assert_eq!(
+            Floor.view(fit).to_string(),
+            "  0   4  \n0 . . . .\n2 # # o #\n"
+        );
+
+        assert_eq!(parse_size("120x40"), Ok((120, 40)));
+        assert!(parse_size("0x40").is_err());
+        assert!(Window::parse("5..5,0..1").is_err());
+        assert!(Window::parse("everything").is_err());
+    }
+
+    #[test]
+    fn draws_images() {
+        let palette = Palette::default().with('o', [255, 255, 0]);
+        let image = Floor.to_image(&palette, 2);
+
+        assert_eq!((image.width, image.height), (16, 8));
+        assert_eq!(image.pixels[0], palette.background);
+        assert_eq!(image.pixels[4 * 16 + 8], [255, 255, 0]);
+        assert_eq!(image.pixels[5 * 16 + 11], [255, 255, 0]);
+        assert_eq!(image.pixels[7 * 16 + 15], palette.foreground);
+
+        // Styled cells fall back to the color of their style
+        let image = Dot.to_image(&Palette::default(), 1);
+        assert_eq!(
+            image.pixels,
+            [Palette::default().background, Color::Red.rgb()]
+        );
+
+        let grid = Grid::from("#..\n.#.");
+        let image = grid.to_image(&Palette::default(), 1);
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(image.pixels[4], Palette::default().foreground);
+        assert_eq!(image.pixels[5], Palette::default().background);
+    }
+
+    #[test]
+    fn keeps_drawings_apart() {
+        let visualizer = Visualizer::new(Sink::Buffer).with_colors(true);
+        visualizer.draw(14, 2, &Dot);
+        visualizer.draw(9, 1, &"rope");
+        visualizer.draw(14, 1, &Dot);
+        visualizer.draw(9, 1, &"rope again");
+
+        let drawings = visualizer.take();
+        let order: Vec<(u8, u8)> = drawings
+            .iter()
+            .map(|drawing| (drawing.day, drawing.part))
+            .collect();
+
+        assert_eq!(order, [(9, 1), (9, 1), (14, 1), (14, 2)]);
+        assert_eq!(drawings[1].text, "rope again");
+        assert!(drawings[2].text.contains("\x1b[1;31m#"));
+        assert!(visualizer.take().is_empty());
+
+        let drawing = Drawing {
+            day: 9,
+            part: 1,
+            text: "rope".to_string(),
+        };
+        assert_eq!(
+            drawing.to_string(),
+            format!(
+                "{}{}{}\nrope\n",
+                "-".repeat(33),
+                " Day 9 part 1 ",
+                "-".repeat(33)
+            )
+        );
+
+        let null = Visualizer::new(Sink::Null);
+        null.draw(1, 1, &"nothing");
+        assert!(null.take().is_empty());
+
+        let ((), drawings) = capture(|| visualize(10, 2, &Dot));
+        assert_eq!(
+            drawings,
+            [Drawing {
+                day: 10,
+                part: 2,
+                text: Dot.to_string(),
+            }]
+        );
+    }
 }
@@ -0,0 +1,381 @@
+use std::{
+    fmt::Write as _,
+    fs::{self, OpenOptions},
+    io::Write as _,
+    path::PathBuf,
+    process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{bench::Stats, solver::Selection};
+
+pub const HISTORY_FILE: &str = "bench-history.csv";
+pub const THRESHOLD: f64 = 10.0;
+
+const HEADER: &str = "commit,timestamp_ms,day,part,runs,min_ns,median_ns,mean_ns,p95_ns,std_dev_ns";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub commit: String,
+    pub timestamp: u64,
+    pub day: u8,
+    pub part: u8,
+    pub stats: Stats,
+}
+
+impl Entry {
+    fn to_line(&self) -> String {
+        let stats = &self.stats;
+
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            self.commit,
+            self.timestamp,
+            self.day,
+            self.part,
+            stats.runs,
+            stats.min.as_nanos(),
+            stats.median.as_nanos(),
+            stats.mean.as_nanos(),
+            stats.p95.as_nanos(),
+            stats.std_dev.as_nanos()
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split(',').collect();
+
+        if fields.len() != 10 {
+            return None;
+        }
+
+        let nanos = |index: usize| fields[index].parse().ok().map(Duration::from_nanos);
+
+        Some(Self {
+            commit: fields[0].to_string(),
+            timestamp: fields[1].parse().ok()?,
+            day: fields[2].parse().ok()?,
+            part: fields[3].parse().ok()?,
+            stats: Stats {
+                runs: fields[4].parse().ok()?,
+                min: nanos(5)?,
+                median: nanos(6)?,
+                mean: nanos(7)?,
+                p95: nanos(8)?,
+                std_dev: nanos(9)?,
+            },
+        })
+    }
+}
+
+/// All entries of one bench invocation.
+#[derive(Debug)]
+pub struct Run {
+    pub commit: String,
+    pub timestamp: u64,
+    pub entries: Vec<Entry>,
+}
+
+impl Run {
+    pub fn name(&self) -> String {
+        format!(
+            "{} ({})",
+            self.commit,
+            format_timestamp(self.timestamp / 1000)
+        )
+    }
+
+    fn get(&self, day: u8, part: u8) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.day == day && entry.part == part)
+    }
+}
+
+/// Benchmark results of earlier runs, appended to a CSV file.
+pub struct History {
+    pub path: PathBuf,
+}
+
+impl History {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<Vec<Entry>, String> {
+        let content = fs::read_to_string(&self.path)
+            .map_err(|error| format!("cannot read {}: {}", self.path.display(), error))?;
+
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| *line != HEADER && !line.trim().is_empty())
+            .map(|(index, line)| {
+                Entry::from_line(line).ok_or_else(|| {
+                    format!("{}:{}: malformed entry", self.path.display(), index + 1)
+                })
+            })
+            .collect()
+    }
+
+    pub fn append(&self, entries: &[Entry]) -> Result<(), String> {
+        let error =
+            |error: std::io::Error| format!("cannot write {}: {}", self.path.display(), error);
+
+        let exists = self.path.exists();
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(error)?;
+
+        let mut content = String::new();
+
+        if !exists {
+            content += HEADER;
+            content += "\n";
+        }
+
+        for entry in entries {
+            content += &entry.to_line();
+            content += "\n";
+        }
+
+        file.write_all(content.as_bytes()).map_err(error)
+    }
+}
+
+/// Groups entries by the run they were recorded in, oldest run first.
+pub fn runs(entries: Vec<Entry>) -> Vec<Run> {
+    let mut runs: Vec<Run> = vec![];
+
+    for entry in entries {
+        match runs.last_mut() {
+            Some(run) if run.commit == entry.commit && run.timestamp == entry.timestamp => {
+                run.entries.push(entry)
+            }
+            _ => runs.push(Run {
+                commit: entry.commit.clone(),
+                timestamp: entry.timestamp,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    runs
+}
+
+/// Finds a run by `latest`, `previous`, a timestamp or a commit prefix,
+/// preferring the most recent match.
+pub fn find_run<'a>(runs: &'a [Run], reference: &str) -> Result<&'a Run, String> {
+    let found = match reference {
+        "latest" => runs.last(),
+        "previous" => runs.iter().rev().nth(1),
+        _ => runs.iter().rev().find(|run| {
+            run.timestamp.to_string() == reference || run.commit.starts_with(reference)
+        }),
+    };
+
+    found.ok_or_else(|| format!("no benchmark run matches `{}`", reference))
+}
+
+pub struct Comparison {
+    pub day: u8,
+    pub part: u8,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Comparison {
+    /// Relative change of the median in percent, positive when slower.
+    pub fn change(&self) -> f64 {
+        let baseline = self.baseline.as_nanos() as f64;
+        let current = self.current.as_nanos() as f64;
+
+        if baseline == 0.0 {
+            return 0.0;
+        }
+
+        (current - baseline) / baseline * 100.0
+    }
+
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+pub fn compare(baseline: &Run, current: &Run, selection: &Selection) -> Vec<Comparison> {
+    current
+        .entries
+        .iter()
+        .filter(|entry| selection.contains(entry.day, entry.part))
+        .filter_map(|entry| {
+            let before = baseline.get(entry.day, entry.part)?;
+
+            Some(Comparison {
+                day: entry.day,
+                part: entry.part,
+                baseline: before.stats.median,
+                current: entry.stats.median,
+            })
+        })
+        .collect()
+}
+
+pub fn render(comparisons: &[Comparison], threshold: f64) -> String {
+    let mut output = format!(
+        "{:<10}{:>13}{:>13}{:>10}\n",
+        "", "baseline", "current", "change"
+    );
+
+    for comparison in comparisons {
+        let change = comparison.change();
+
+        let verdict = if comparison.is_regression(threshold) {
+            "  REGRESSION"
+        } else if change < -threshold {
+            "  faster"
+        } else {
+            ""
+        };
+
+        writeln!(
+            output,
+            "{:<10}{:>13}{:>13}{:>9.1}%{}",
+            format!("day{}pt{}", comparison.day, comparison.part),
+            format!("{:.2?}", comparison.baseline),
+            format!("{:.2?}", comparison.current),
+            change,
+            verdict
+        )
+        .unwrap();
+    }
+
+    output
+}
+
+/// `git describe` of the working tree, marking uncommitted changes.
+pub fn current_commit() -> String {
+    process::Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Milliseconds since the epoch, fine enough to tell runs apart.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Formats seconds since the epoch as a UTC date and time.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Days to civil date, after Howard Hinnant's `civil_from_days`
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{compare, find_run, format_timestamp, runs, Entry};
+    use crate::{bench::Stats, solver::Selection};
+
+    fn entry(commit: &str, timestamp: u64, day: u8, median: u64) -> Entry {
+        let median = Duration::from_micros(median);
+
+        Entry {
+            commit: commit.to_string(),
+            timestamp,
+            day,
+            part: 1,
+            stats: Stats {
+                runs: 10,
+                min: median,
+                median,
+                mean: median,
+                p95: median,
+                std_dev: Duration::ZERO,
+            },
+        }
+    }
+
+    #[test]
+    fn entry_line_round_trip() {
+        let entry = entry("abc1234-dirty", 1671000000, 16, 1500);
+
+        assert_eq!(Entry::from_line(&entry.to_line()), Some(entry));
+        assert_eq!(Entry::from_line("abc,1,2"), None);
+    }
+
+    #[test]
+    fn compare_runs() {
+        let runs = runs(vec![
+            entry("aaa", 1, 16, 100),
+            entry("aaa", 1, 19, 100),
+            entry("bbb", 2, 16, 125),
+            entry("bbb", 2, 19, 50),
+        ]);
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(find_run(&runs, "latest").unwrap().commit, "bbb");
+        assert_eq!(find_run(&runs, "previous").unwrap().commit, "aaa");
+        assert_eq!(find_run(&runs, "aa").unwrap().timestamp, 1);
+        assert!(find_run(&runs, "ccc").is_err());
+
+        let selection = Selection {
+            days: vec![16, 19],
+            part: None,
+            explicit: false,
+        };
+
+        let comparisons = compare(&runs[0], &runs[1], &selection);
+
+        assert_eq!(comparisons[0].change(), 25.0);
+        assert!(comparisons[0].is_regression(10.0));
+        assert!(!comparisons[0].is_regression(30.0));
+        assert_eq!(comparisons[1].change(), -50.0);
+        assert!(!comparisons[1].is_regression(10.0));
+    }
+
+    #[test]
+    fn format_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(1671235200), "2022-12-17 00:00:00 UTC");
+        assert_eq!(format_timestamp(951827696), "2000-02-29 12:34:56 UTC");
+    }
+}
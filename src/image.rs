@@ -0,0 +1,338 @@
+//! Raster images of graphs, written as binary PPM or as PNG. The PNG encoder
+//! only stores the pixels without compressing them, which every reader
+//! understands and keeps it free of dependencies.
+
+use std::{fs, path::Path};
+
+pub type Rgb = [u8; 3];
+
+/// The colors cells are drawn with, by the character they show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    /// The color of empty cells.
+    pub background: Rgb,
+    /// The color of cells with neither a color of their own nor a style.
+    pub foreground: Rgb,
+    pub colors: Vec<(char, Rgb)>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            background: [15, 15, 35],
+            foreground: [204, 204, 204],
+            colors: vec![],
+        }
+    }
+}
+
+impl Palette {
+    /// Draws the cells showing `value` in `color`.
+    pub fn with(mut self, value: char, color: Rgb) -> Self {
+        self.colors.retain(|(seen, _)| *seen != value);
+        self.colors.push((value, color));
+        self
+    }
+
+    pub fn get(&self, value: char) -> Option<Rgb> {
+        self.colors
+            .iter()
+            .find(|(seen, _)| *seen == value)
+            .map(|(_, color)| *color)
+    }
+
+    /// Reads a palette like `background=000000,#=ffffff,o=e0c050`, with hex
+    /// colors for the background, the foreground and single characters.
+    pub fn parse(palette: &str) -> Result<Self, String> {
+        let mut output = Self::default();
+
+        for entry in palette.split(',').filter(|entry| !entry.is_empty()) {
+            let invalid = || {
+                format!(
+                    "invalid palette entry `{}`, expected a character and a color like `#=ffffff`",
+                    entry
+                )
+            };
+
+            let (name, color) = entry.rsplit_once('=').ok_or_else(invalid)?;
+            let color = parse_rgb(color).ok_or_else(invalid)?;
+            let mut chars = name.chars();
+
+            match (name, chars.next(), chars.next()) {
+                ("background", ..) => output.background = color,
+                ("foreground", ..) => output.foreground = color,
+                (_, Some(value), None) => output = output.with(value, color),
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+fn parse_rgb(color: &str) -> Option<Rgb> {
+    let color = color.strip_prefix('#').unwrap_or(color);
+
+    if color.len() != 6 || !color.is_ascii() {
+        return None;
+    }
+
+    let channel = |index: usize| u8::from_str_radix(&color[index..index + 2], 16).ok();
+
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// The pixels row by row, from the top left.
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width as usize * height as usize],
+        }
+    }
+
+    /// Paints the square of `size` pixels from `x` and `y`, clipped to the
+    /// image.
+    pub fn fill(&mut self, x: u32, y: u32, size: u32, color: Rgb) {
+        for pixel_y in y..(y + size).min(self.height) {
+            let row = pixel_y as usize * self.width as usize;
+
+            for pixel_x in x..(x + size).min(self.width) {
+                self.pixels[row + pixel_x as usize] = color;
+            }
+        }
+    }
+
+    /// Draws `image` with its top left corner at `x` and `y`, clipped to
+    /// this image.
+    pub fn paste(&mut self, x: u32, y: u32, image: &Image) {
+        for (row, pixels) in image.rows().enumerate() {
+            let pixel_y = y as usize + row;
+
+            if pixel_y >= self.height as usize || x >= self.width {
+                continue;
+            }
+
+            let start = pixel_y * self.width as usize + x as usize;
+            let length = pixels.len().min((self.width - x) as usize);
+
+            self.pixels[start..start + length].copy_from_slice(&pixels[..length]);
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[Rgb]> {
+        self.pixels.chunks(self.width.max(1) as usize)
+    }
+
+    /// The image as a binary PPM (`P6`) file.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut output = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        output.extend(self.pixels.iter().flatten());
+        output
+    }
+
+    /// Reads a binary PPM file with one byte per channel, like the ones
+    /// [`Image::to_ppm`] writes.
+    pub fn from_ppm(bytes: &[u8]) -> Result<Self, String> {
+        let invalid = || "not a binary PPM image with 8-bit channels".to_string();
+
+        // The magic number and the three numbers of the header are separated
+        // by whitespace, with a single character ending the header
+        let mut fields = vec![];
+        let mut start = 0;
+
+        while fields.len() < 4 {
+            let from = start
+                + bytes[start..]
+                    .iter()
+                    .position(|byte| !byte.is_ascii_whitespace())
+                    .ok_or_else(invalid)?;
+            let to = from
+                + bytes[from..]
+                    .iter()
+                    .position(|byte| byte.is_ascii_whitespace())
+                    .ok_or_else(invalid)?;
+
+            fields.push(std::str::from_utf8(&bytes[from..to]).map_err(|_| invalid())?);
+            start = to + 1;
+        }
+
+        let number = |field: &str| field.parse::<u32>().map_err(|_| invalid());
+        let (width, height) = (number(fields[1])?, number(fields[2])?);
+
+        if fields[0] != "P6" || fields[3] != "255" {
+            return Err(invalid());
+        }
+
+        let data = &bytes[start..];
+        if data.len() != width as usize * height as usize * 3 {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels: data
+                .chunks(3)
+                .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                .collect(),
+        })
+    }
+
+    /// The image as an 8-bit RGB PNG file.
+    pub fn to_png(&self) -> Vec<u8> {
+        // Every row starts with its filter type, none
+        let mut data = vec![];
+        for row in self.rows() {
+            data.push(0);
+            data.extend(row.iter().flatten());
+        }
+
+        let mut header = vec![];
+        header.extend(self.width.to_be_bytes());
+        header.extend(self.height.to_be_bytes());
+        // 8 bits per channel, RGB, deflate, adaptive filtering, no interlace
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut output = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut output, b"IHDR", &header);
+        png_chunk(&mut output, b"IDAT", &zlib_stored(&data));
+        png_chunk(&mut output, b"IEND", &[]);
+        output
+    }
+
+    /// Writes the image to `path`, as a PNG or a PPM file by its extension.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let bytes = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("png") => self.to_png(),
+            Some("ppm") => self.to_ppm(),
+            _ => {
+                return Err(format!(
+                    "cannot tell the format of {}, expected a .png or .ppm file",
+                    path.display()
+                ))
+            }
+        };
+
+        fs::write(path, bytes)
+            .map_err(|error| format!("cannot write {}: {}", path.display(), error))
+    }
+}
+
+fn png_chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    output.extend((data.len() as u32).to_be_bytes());
+    output.extend(kind);
+    output.extend(data);
+    output.extend(crc32(&[kind, data]).to_be_bytes());
+}
+
+/// A zlib stream of `data` in stored deflate blocks, which hold up to 65535
+/// bytes each.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, no dictionary and the check bits of the
+    // header set
+    let mut output = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+
+    if blocks.peek().is_none() {
+        output.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let length = block.len() as u16;
+
+        output.push(blocks.peek().is_none() as u8);
+        output.extend(length.to_le_bytes());
+        output.extend((!length).to_le_bytes());
+        output.extend(block);
+    }
+
+    output.extend(adler32(data).to_be_bytes());
+    output
+}
+
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in parts.iter().copied().flatten() {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xedb88320,
+                _ => crc >> 1,
+            };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MODULO: u32 = 65521;
+
+    let (a, b) = data.iter().fold((1, 0), |(a, b), byte| {
+        let a = (a + *byte as u32) % MODULO;
+        (a, (b + a) % MODULO)
+    });
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adler32, crc32, zlib_stored, Image, Palette};
+
+    #[test]
+    fn writes_ppm_and_png() {
+        let mut image = Image::new(3, 2, [0, 0, 0]);
+        image.fill(1, 1, 4, [255, 128, 0]);
+
+        assert_eq!(
+            image.to_ppm(),
+            [
+                b"P6\n3 2\n255\n".as_slice(),
+                &[0, 0, 0, 0, 0, 0, 0, 0, 0],
+                &[0, 0, 0, 255, 128, 0, 255, 128, 0],
+            ]
+            .concat()
+        );
+
+        assert_eq!(Image::from_ppm(&image.to_ppm()), Ok(image.clone()));
+        assert!(Image::from_ppm(b"P6\n3 2\n255\n").is_err());
+
+        let png = image.to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x03\0\0\0\x02"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+
+        assert_eq!(crc32(&[b"123456789"]), 0xcbf43926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+
+        // Long data is split into blocks, the last one marked as final
+        let stored = zlib_stored(&[7; 70000]);
+        assert_eq!(stored.len(), 2 + 5 + 65535 + 5 + 4465 + 4);
+        assert_eq!(stored[2..7], [0, 0xff, 0xff, 0, 0]);
+        assert_eq!(stored[65542..65547], [1, 0x71, 0x11, 0x8e, 0xee]);
+    }
+
+    #[test]
+    fn parses_palettes() {
+        let palette = Palette::parse("background=000000,#=#ffffff,==e0c050").unwrap();
+
+        assert_eq!(palette.background, [0, 0, 0]);
+        assert_eq!(palette.get('#'), Some([255, 255, 255]));
+        assert_eq!(palette.get('='), Some([224, 192, 80]));
+        assert_eq!(palette.get('o'), None);
+
+        assert!(Palette::parse("#=fff").is_err());
+        assert!(Palette::parse("sand=ffffff").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+pub const INPUT_DIR: &str = "input";
+pub const EXAMPLE_DIR: &str = "example_input";
+
+/// Why the input of a day could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The input holds nothing but whitespace, like the files of the days
+    /// whose input was not added yet.
+    Empty(String),
+    Unreadable(String),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Empty(name) => write!(f, "{} is empty", name),
+            LoadError::Unreadable(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Where the puzzle input of a day is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `dayN.txt` inside a directory, `input/` unless told otherwise.
+    Directory(PathBuf),
+    File(PathBuf),
+    Stdin,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Directory(PathBuf::from(INPUT_DIR))
+    }
+}
+
+impl Source {
+    pub fn example() -> Self {
+        Source::Directory(PathBuf::from(EXAMPLE_DIR))
+    }
+
+    /// Interprets a `--input` argument, `-` meaning stdin.
+    pub fn from_arg(arg: &str) -> Self {
+        let path = PathBuf::from(arg);
+
+        if arg == "-" {
+            Source::Stdin
+        } else if path.is_dir() {
+            Source::Directory(path)
+        } else {
+            Source::File(path)
+        }
+    }
+
+    /// Whether the source only holds the input of a single day.
+    pub fn is_single(&self) -> bool {
+        !matches!(self, Source::Directory(_))
+    }
+
+    pub fn load(&self, day: u8) -> Result<String, LoadError> {
+        let (name, content) = match self {
+            Source::Directory(directory) => {
+                let path = directory.join(format!("day{}.txt", day));
+                (path.display().to_string(), read_file(&path)?)
+            }
+            Source::File(path) => (path.display().to_string(), read_file(path)?),
+            Source::Stdin => {
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content).map_err(|error| {
+                    LoadError::Unreadable(format!("cannot read stdin: {}", error))
+                })?;
+                ("stdin".to_string(), content)
+            }
+        };
+
+        if content.trim().is_empty() {
+            return Err(LoadError::Empty(name));
+        }
+
+        Ok(content)
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, LoadError> {
+    fs::read_to_string(path).map_err(|error| {
+        LoadError::Unreadable(format!("cannot read {}: {}", path.display(), error))
+    })
+}
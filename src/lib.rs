@@ -0,0 +1,47 @@
+//! Advent of Code 2022 solutions.
+//!
+//! Every day has a module with a `parse` function turning the puzzle input
+//! into its own type and `solve_1` and `solve_2` functions answering the two
+//! parts. The days are registered in [`solver::SOLVERS`] for the runner, and
+//! [`extra`] holds the helpers for drawing puzzles in the terminal and, through
+//! [`image`], as pictures.
+
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+pub mod error;
+pub mod extra;
+pub mod history;
+pub mod image;
+pub mod input;
+pub mod parallel;
+pub mod record;
+pub mod report;
+pub mod scaffold;
+pub mod solver;
@@ -1,437 +1,377 @@
 use std::{
-    env,
+    env, io,
+    path::Path,
+    process,
     time::{Duration, Instant},
 };
 
-use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
-
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-mod extra;
-
-const PRINT_WIDTH: usize = 80;
-const TIME_PAD_WIDTH: usize = 15;
+use aoc2022::{
+    answers::{AnswerMode, AnswerStore},
+    bench,
+    cli::{self, BenchOptions, Command, CompareOptions, RunOptions},
+    extra::{self, Sink, Visualizer},
+    history::{self, History},
+    input::LoadError,
+    parallel::prelude::*,
+    report::{Format, Outcome, Report, Summary},
+    scaffold,
+    solver::{self, Cancel, Selection, DAYS, PARTS},
+};
 
 fn main() {
-    let enumerated_inputs: Vec<(usize, &str)> = vec![
-        include_str!("../input/day1.txt"),
-        include_str!("../input/day1.txt"),
-        include_str!("../input/day2.txt"),
-        include_str!("../input/day2.txt"),
-        include_str!("../input/day3.txt"),
-        include_str!("../input/day3.txt"),
-        include_str!("../input/day4.txt"),
-        include_str!("../input/day4.txt"),
-        include_str!("../input/day5.txt"),
-        include_str!("../input/day5.txt"),
-        include_str!("../input/day6.txt"),
-        include_str!("../input/day6.txt"),
-        include_str!("../input/day7.txt"),
-        include_str!("../input/day7.txt"),
-        include_str!("../input/day8.txt"),
-        include_str!("../input/day8.txt"),
-        include_str!("../input/day9.txt"),
-        include_str!("../input/day9.txt"),
-        include_str!("../input/day10.txt"),
-        include_str!("../input/day10.txt"),
-        include_str!("../input/day11.txt"),
-        include_str!("../input/day11.txt"),
-        include_str!("../input/day12.txt"),
-        include_str!("../input/day12.txt"),
-        include_str!("../input/day13.txt"),
-        include_str!("../input/day13.txt"),
-        include_str!("../input/day14.txt"),
-        include_str!("../input/day14.txt"),
-        include_str!("../input/day15.txt"),
-        include_str!("../input/day15.txt"),
-        include_str!("../input/day16.txt"),
-        include_str!("../input/day16.txt"),
-        include_str!("../input/day17.txt"),
-        include_str!("../input/day17.txt"),
-        include_str!("../input/day18.txt"),
-        include_str!("../input/day18.txt"),
-        include_str!("../input/day19.txt"),
-        include_str!("../input/day19.txt"),
-        include_str!("../input/day20.txt"),
-        include_str!("../input/day20.txt"),
-        include_str!("../input/day21.txt"),
-        include_str!("../input/day21.txt"),
-        include_str!("../input/day22.txt"),
-        include_str!("../input/day22.txt"),
-        include_str!("../input/day23.txt"),
-        include_str!("../input/day23.txt"),
-        include_str!("../input/day24.txt"),
-        include_str!("../input/day24.txt"),
-        include_str!("../input/day25.txt"),
-        include_str!("../input/day25.txt"),
-    ]
-    .into_iter()
-    .enumerate()
-    .collect();
-
-    let start = Instant::now();
+    let RunOptions {
+        selection,
+        input,
+        format,
+        answers,
+        answer_dir,
+        timeout,
+        threads,
+        visualize,
+    } = match cli::parse(env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Bench(options)) => {
+            run_bench(options);
+            return;
+        }
+        Ok(Command::Compare(options)) => {
+            run_compare(options);
+            return;
+        }
+        Ok(Command::NewDay(day)) => {
+            new_day(day);
+            return;
+        }
+        Ok(Command::List) => {
+            list_solvers();
+            return;
+        }
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, cli::USAGE);
+            process::exit(2);
+        }
+    };
 
-    let mut results: Vec<(usize, Duration, String)> = enumerated_inputs
-        .par_iter()
-        .map(|(index, input)| {
-            let day = (index) / 2 + 1;
-            let part = (index) % 2 + 1;
+    use_threads(threads);
 
-            if let Ok(day_str) = env::var("DAY") {
-                if day_str.parse::<usize>().unwrap() != day {
-                    return (*index, Duration::ZERO, "SKIPPED".to_string());
-                }
-
-                if let Ok(part_str) = env::var("PART") {
-                    if part_str.parse::<usize>().unwrap() != part {
-                        return (*index, Duration::ZERO, "SKIPPED".to_string());
-                    }
-                }
-            }
+    // Drawings are kept until the results are out, so the parts solving in
+    // parallel do not draw over each other
+    let sink = visualize.unwrap_or_else(|| default_sink(&selection, format));
+    // Only the stream the drawings end up on decides whether they get color
+    let colors = match (&sink, format) {
+        (Sink::Buffer, Format::Json | Format::Csv) => extra::colors(io::stderr()),
+        _ => extra::colors(io::stdout()),
+    };
+    extra::use_visualizer(Visualizer::new(sink).with_colors(colors));
 
-            let start = Instant::now();
+    let inputs: Vec<(u8, Result<String, LoadError>)> = selection
+        .days
+        .iter()
+        .filter(|day| solver::find(**day).is_some())
+        .map(|day| (*day, input.load(*day)))
+        .collect();
 
-            let result = match (day, part) {
-                (1, 1) => day1::solve_1(input),
-                (1, 2) => day1::solve_2(input),
-                (2, 1) => day2::solve_1(input),
-                (2, 2) => day2::solve_2(input),
-                (3, 1) => day3::solve_1(input),
-                (3, 2) => day3::solve_2(input),
-                (4, 1) => day4::solve_1(input),
-                (4, 2) => day4::solve_2(input),
-                (5, 1) => day5::solve_1(input),
-                (5, 2) => day5::solve_2(input),
-                (6, 1) => day6::solve_1(input),
-                (6, 2) => day6::solve_2(input),
-                (7, 1) => day7::solve_1(input),
-                (7, 2) => day7::solve_2(input),
-                (8, 1) => day8::solve_1(input),
-                (8, 2) => day8::solve_2(input),
-                (9, 1) => day9::solve_1(input),
-                (9, 2) => day9::solve_2(input),
-                (10, 1) => day10::solve_1(input),
-                (10, 2) => day10::solve_2(input),
-                (11, 1) => day11::solve_1(input),
-                (11, 2) => day11::solve_2(input),
-                (12, 1) => day12::solve_1(input),
-                (12, 2) => day12::solve_2(input),
-                (13, 1) => day13::solve_1(input),
-                (13, 2) => day13::solve_2(input),
-                (14, 1) => day14::solve_1(input),
-                (14, 2) => day14::solve_2(input),
-                (15, 1) => day15::solve_1(input),
-                (15, 2) => day15::solve_2(input),
-                (16, 1) => day16::solve_1(input),
-                (16, 2) => day16::solve_2(input),
-                (17, 1) => day17::solve_1(input),
-                (17, 2) => day17::solve_2(input),
-                (18, 1) => day18::solve_1(input),
-                (18, 2) => day18::solve_2(input),
-                (19, 1) => day19::solve_1(input),
-                (19, 2) => day19::solve_2(input),
-                (20, 1) => day20::solve_1(input),
-                (20, 2) => day20::solve_2(input),
-                (21, 1) => day21::solve_1(input),
-                (21, 2) => day21::solve_2(input),
-                _ => unreachable!(),
-            };
+    let start = Instant::now();
 
-            let duration = start.elapsed();
+    let reports: Vec<Report> = (1..=DAYS)
+        .into_par_iter()
+        .flat_map_iter(|day| {
+            let input = inputs
+                .iter()
+                .find(|(input_day, _)| *input_day == day)
+                .map(|(_, input)| input);
 
-            (*index, duration, result)
+            run_day(day, &selection, input, timeout)
         })
         .collect();
 
-    let duration = start.elapsed();
-
-    results.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
-
-    let mut sum_duration = Duration::ZERO;
-
-    for (index, duration, result) in results {
-        sum_duration += duration;
+    let mut summary = Summary::new(reports, start.elapsed());
+    let store = AnswerStore::new(answer_dir);
+
+    match answers {
+        AnswerMode::Off => {}
+        AnswerMode::Verify => store.verify(&mut summary.reports),
+        AnswerMode::Record => match store.record(&summary.reports) {
+            Ok(recorded) => eprintln!(
+                "recorded {} answers in {}",
+                recorded,
+                store.directory.display()
+            ),
+            Err(error) => {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        },
+    }
 
-        let day = (index) / 2 + 1;
-        let part = (index % 2) + 1;
-        let multiline = day == 10 && part == 2;
+    print!("{}", summary.render(format));
+
+    // Drawings would end up in the middle of machine readable output
+    for drawing in extra::visualizer()
+        .map(Visualizer::take)
+        .unwrap_or_default()
+    {
+        match format {
+            Format::Human => print!("\n{}", drawing),
+            _ => eprint!("\n{}", drawing),
+        }
+    }
 
-        let report = display_with_duration(
-            80,
-            15,
-            multiline,
-            format!("day{}pt{}: ", day, part).as_str(),
-            "-",
-            result.as_str(),
-            duration,
-        );
+    for details in [summary.diagnostics(), summary.mismatches()] {
+        if details.is_empty() {
+            continue;
+        }
 
-        println!("{}", report);
+        if format == Format::Human {
+            print!("\n{}", details);
+        } else {
+            eprint!("{}", details);
+        }
     }
 
-    println!();
-
-    println!(
-        "{}",
-        display_with_duration(
-            PRINT_WIDTH,
-            TIME_PAD_WIDTH,
-            false,
-            "> ",
-            "-",
-            "total thread time",
-            sum_duration,
-        )
-    );
-
-    println!(
-        "{}",
-        display_with_duration(
-            PRINT_WIDTH,
-            TIME_PAD_WIDTH,
-            false,
-            "> ",
-            "-",
-            "real time",
-            duration,
-        )
-    );
-}
-
-fn display_with_duration(
-    target_len: usize,
-    time_len: usize,
-    multiline: bool,
-    prefix: &str,
-    suffix: &str,
-    content: &str,
-    duration: Duration,
-) -> String {
-    let mut output = String::new();
-    output += prefix;
-    if multiline {
-        output += "\n";
+    if summary.has_failures() {
+        process::exit(1);
     }
-    let duration_str = format!("{:?}", duration);
-    let content_len = content.lines().map(|line| line.len()).max().unwrap();
-    let pad_len =
-        target_len - if !multiline { prefix.len() } else { 0 } - content_len - suffix.len();
-
-    let main_pad = " ".repeat(pad_len - time_len);
-    let time_pad = " ".repeat(time_len - duration_str.len());
-    output += format!(
-        "{}{}{}{}{}",
-        content, main_pad, suffix, time_pad, duration_str
-    )
-    .as_str();
-
-    output
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        day1, day10, day11, day12, day13, day14, day15, day16, day17, day2, day3, day4, day5, day6,
-        day7, day8, day8::TreeGrid, day9,
-    };
-
-    #[test]
-    fn day1_eq_example() {
-        let input = include_str!("../example_input/day1.txt");
-        assert_eq!(day1::solve_1(input), "24000");
-        assert_eq!(day1::solve_2(input), "45000");
-    }
+/// Drawings are shown when the user picked what to run and reads the
+/// results, unless `VISUALIZE` is set to anything but `1` or `true`.
+fn default_sink(selection: &Selection, format: Format) -> Sink {
+    let disabled = env::var("VISUALIZE").is_ok_and(|value| value != "1" && value != "true");
 
-    #[test]
-    fn day2_eq_example() {
-        let input = include_str!("../example_input/day2.txt");
-        assert_eq!(day2::solve_1(input), "15");
-        assert_eq!(day2::solve_2(input), "12");
+    match selection.explicit && format == Format::Human && !disabled {
+        true => Sink::Buffer,
+        false => Sink::Null,
     }
+}
 
-    #[test]
-    fn day3_eq_example() {
-        let input = include_str!("../example_input/day3.txt");
-        assert_eq!(day3::solve_1(input), "157");
-        assert_eq!(day3::solve_2(input), "70");
+/// Sizes the thread pool when asked to, before anything runs on it.
+fn use_threads(threads: Option<usize>) {
+    #[cfg(feature = "parallel")]
+    if let Some(Err(error)) = threads.map(aoc2022::parallel::use_threads) {
+        eprintln!("error: {}", error);
+        process::exit(2);
     }
 
-    #[test]
-    fn day4_eq_example() {
-        let input = include_str!("../example_input/day4.txt");
-        assert_eq!(day4::solve_1(input), "2");
-        assert_eq!(day4::solve_2(input), "4");
-    }
+    // The option is only accepted with the feature
+    #[cfg(not(feature = "parallel"))]
+    debug_assert!(threads.is_none());
+}
 
-    #[test]
-    fn day5_eq_example() {
-        let input = include_str!("../example_input/day5.txt");
-        assert_eq!(day5::solve_1(input), "CMZ".to_string());
-        assert_eq!(day5::solve_2(input), "MCD".to_string());
-    }
+/// Parses the input of one day once, then solves its selected parts in
+/// parallel on the shared puzzle.
+fn run_day(
+    day: u8,
+    selection: &Selection,
+    input: Option<&Result<String, LoadError>>,
+    timeout: Option<Duration>,
+) -> Vec<Report> {
+    let unsolved = |outcome: Outcome, parse: Option<Duration>| {
+        (1..=PARTS)
+            .map(|part| {
+                if !selection.contains(day, part) {
+                    return Report::new(day, part, Duration::ZERO, Outcome::Skipped);
+                }
 
-    #[test]
-    fn day6_eq_example() {
-        let input = include_str!("../example_input/day6.txt");
-        assert_eq!(day6::solve_1(input), "7");
-        assert_eq!(day6::solve_2(input), "19");
-    }
+                let mut report = Report::new(day, part, Duration::ZERO, outcome.clone());
+                report.parse = parse;
+                report
+            })
+            .collect()
+    };
 
-    #[test]
-    fn day7_eq_example() {
-        let input = include_str!("../example_input/day7.txt");
-        assert_eq!(day7::solve_1(input), "95437");
-        assert_eq!(day7::solve_2(input), "24933642");
+    if !(1..=PARTS).any(|part| selection.contains(day, part)) {
+        return unsolved(Outcome::Skipped, None);
     }
 
-    #[test]
-    fn day8_eq_example() {
-        let input = include_str!("../example_input/day8.txt");
-        assert_eq!(day8::solve_1(input), "21");
-        let scan = TreeGrid::from(input);
-        assert_eq!(scan.scenic_score(&day8::Coord { x: 2, y: 1 }), 4);
-        assert_eq!(scan.scenic_score(&day8::Coord { x: 0, y: 0 }), 0);
-        assert_eq!(day8::solve_2(input), "8")
-    }
+    let Some(solver) = solver::find(day) else {
+        return unsolved(Outcome::NotImplemented, None);
+    };
 
-    #[test]
-    fn day9_eq_example() {
-        let input = include_str!("../example_input/day9.txt");
-        assert_eq!(day9::solve_1(input), "13");
-        assert_eq!(day9::solve_2(input), "1");
-        let input = include_str!("../example_input/day9pt2.txt");
-        assert_eq!(day9::solve_2(input), "36");
-    }
+    let input = match input {
+        Some(Ok(input)) => input,
+        // Everyone adds their own inputs, running everything leaves out the
+        // days still missing one
+        Some(Err(LoadError::Empty(_))) if !selection.explicit => {
+            return unsolved(Outcome::Skipped, None)
+        }
+        Some(Err(error)) => return unsolved(Outcome::Failed(error.to_string()), None),
+        None => unreachable!("inputs loaded for every selected day"),
+    };
 
-    #[test]
-    fn day10_eq_example() {
-        let input = include_str!("../example_input/day10.txt");
+    let start = Instant::now();
+    let puzzle = solver::isolate(|| solver.parse(input));
+    let parse = start.elapsed();
 
-        assert_eq!(day10::solve_1(input), "13140");
+    let puzzle = match puzzle {
+        Ok(Ok(puzzle)) => puzzle,
+        Ok(Err(error)) => return unsolved(Outcome::InvalidInput(error), Some(parse)),
+        Err(message) => return unsolved(Outcome::Panicked(message), Some(parse)),
+    };
 
-        assert_eq!(
-            day10::solve_2(input),
-            r"##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######....."
-        );
-    }
+    (1..=PARTS)
+        .into_par_iter()
+        .map(|part| {
+            if !selection.contains(day, part) {
+                return Report::new(day, part, Duration::ZERO, Outcome::Skipped);
+            }
 
-    #[test]
-    fn day11_eq_example() {
-        let input = include_str!("../example_input/day11.txt");
+            let start = Instant::now();
+            let cancel = timeout.map_or_else(Cancel::never, Cancel::after);
+
+            let outcome = match solver::isolate(|| solver.solve(part, puzzle.as_ref(), &cancel)) {
+                // Solvers that do not poll the token are only caught afterwards
+                Ok(_) if cancel.is_cancelled() => Outcome::TimedOut,
+                Ok(Ok(answer)) => Outcome::Solved(answer),
+                Ok(Err(error)) => Outcome::InvalidInput(error.locate(day, input)),
+                Err(message) => Outcome::Panicked(message),
+            };
 
-        assert_eq!(day11::solve_1(input), "10605");
-        assert_eq!(day11::solve_2(input), "2713310158");
-    }
+            let mut report = Report::new(day, part, start.elapsed(), outcome);
+            report.parse = Some(parse);
+            report
+        })
+        .collect()
+}
 
-    #[test]
-    fn day12_eq_example() {
-        let input = include_str!("../example_input/day12.txt");
+/// Benchmarks the selected parts one after another so that they do not
+/// compete with each other for the thread pool.
+fn run_bench(options: BenchOptions) {
+    use_threads(options.threads);
 
-        assert_eq!(day12::solve_1(input), "31");
-        assert_eq!(day12::solve_2(input), "29");
-    }
+    let commit = history::current_commit();
+    let timestamp = history::now();
+    let mut entries = vec![];
 
-    #[test]
-    fn day13_eq_example() {
-        let input = include_str!("../example_input/day13.txt");
+    println!("{}", bench::header());
 
-        assert_eq!(day13::solve_1(input), "13");
-        assert_eq!(day13::solve_2(input), "140");
-    }
+    for &day in &options.selection.days {
+        let solver = solver::find(day);
 
-    #[test]
-    fn day14_eq_example() {
-        let input = include_str!("../example_input/day14.txt");
+        // Only the solves are timed, on a puzzle parsed once per day
+        let puzzle = solver.map(|solver| {
+            let input = options
+                .input
+                .load(day)
+                .map_err(|error| format!("ERROR: {}", error))?;
 
-        assert_eq!(day14::solve_1(input), "24");
-        assert_eq!(day14::solve_2(input), "93");
-    }
+            match solver::isolate(|| solver.parse(&input)) {
+                Ok(Ok(puzzle)) => Ok(puzzle),
+                Ok(Err(error)) => Err(format!("INVALID INPUT: {}", error)),
+                Err(message) => Err(format!("FAILED: {}", message)),
+            }
+        });
 
-    #[test]
-    fn day15_eq_example() {
-        let input = include_str!("../example_input/day15.txt");
+        for part in 1..=PARTS {
+            if !options.selection.contains(day, part) {
+                continue;
+            }
 
-        assert_eq!(day15::solve_1(input), "26");
-        assert_eq!(day15::solve_2(input), "56000011");
-    }
+            let result = match (solver, &puzzle) {
+                (Some(solver), Some(Ok(puzzle))) => {
+                    bench::measure(solver, part, puzzle.as_ref(), options.warmup, options.runs)
+                        .map_err(|message| format!("FAILED: {}", message))
+                }
+                (Some(_), Some(Err(error))) => Err(error.clone()),
+                _ => Err("NOT IMPLEMENTED".to_string()),
+            };
 
-    #[test]
-    fn day16_eq_example() {
-        let input = include_str!("../example_input/day16.txt");
+            println!("{}", bench::row(day, part, &result));
 
-        assert_eq!(day16::solve_1(input), "1651");
-        assert_eq!(day16::solve_2(input), "1707");
+            if let Ok(stats) = result {
+                entries.push(history::Entry {
+                    commit: commit.clone(),
+                    timestamp,
+                    day,
+                    part,
+                    stats,
+                });
+            }
+        }
     }
 
-    #[test]
-    fn day17_eq_example() {
-        let input = include_str!("../example_input/day17.txt");
-
-        assert_eq!(day17::solve_1(input), "3068");
-        assert_eq!(day17::solve_2(input), "1514285714288");
+    if !options.save || entries.is_empty() {
+        return;
     }
 
-    #[test]
-    fn day18_eq_example() {
-        use crate::day18;
+    let history = History::new(options.history);
+
+    match history.append(&entries) {
+        Ok(()) => eprintln!(
+            "saved {} results for {} in {}",
+            entries.len(),
+            commit,
+            history.path.display()
+        ),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
+}
 
-        let input = include_str!("../example_input/day18.txt");
+/// Compares two runs from the benchmark history, failing on a regression.
+fn run_compare(options: CompareOptions) {
+    let history = History::new(options.history);
 
-        assert_eq!(day18::solve_1(input), "64");
-        assert_eq!(day18::solve_2(input), "58");
-    }
+    let comparisons = history.load().and_then(|entries| {
+        let runs = history::runs(entries);
+        let baseline = history::find_run(&runs, &options.baseline)?;
+        let current = history::find_run(&runs, &options.current)?;
 
-    #[test]
-    fn day19_eq_example() {
-        use crate::day19;
+        println!("baseline: {}", baseline.name());
+        println!("current:  {}\n", current.name());
 
-        let input = include_str!("../example_input/day19.txt");
+        Ok(history::compare(baseline, current, &options.selection))
+    });
 
-        assert_eq!(day19::solve_1(input), "33");
-    }
+    let comparisons = match comparisons {
+        Ok(comparisons) => comparisons,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(2);
+        }
+    };
 
-    #[test]
-    fn day20_eq_example() {
-        use crate::day20::{solve_1, solve_2};
+    print!("{}", history::render(&comparisons, options.threshold));
 
-        let input = include_str!("../example_input/day20.txt");
+    let regressions = comparisons
+        .iter()
+        .filter(|comparison| comparison.is_regression(options.threshold))
+        .count();
 
-        assert_eq!(solve_1(input), "3");
-        assert_eq!(solve_2(input), "1623178306")
+    if regressions > 0 {
+        eprintln!(
+            "{} parts regressed by more than {}%",
+            regressions, options.threshold
+        );
+        process::exit(1);
     }
+}
 
-    #[test]
-    fn day21_eq_example() {
-        use crate::day21::{solve_1, solve_2};
-
-        let input = include_str!("../example_input/day21.txt");
+fn new_day(day: u8) {
+    match scaffold::new_day(Path::new("."), day) {
+        Ok(written) => {
+            for path in written {
+                println!("wrote {}", path.display());
+            }
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
+}
 
-        assert_eq!(solve_1(input), "152");
-        assert_eq!(solve_2(input), "301")
+fn list_solvers() {
+    for day in 1..=DAYS {
+        match solver::find(day) {
+            Some(solver) => println!("day{:<3} {}", day, solver.title()),
+            None => println!("day{:<3} (not implemented)", day),
+        }
     }
 }
@@ -0,0 +1,61 @@
+//! Data parallelism for the runner and the solvers. With the `parallel`
+//! feature the prelude is rayon's, without it the same methods fall back to
+//! plain iterators running on the calling thread, so callers only import
+//! [`prelude`] and work either way.
+
+#[cfg(feature = "parallel")]
+pub mod prelude {
+    pub use rayon::prelude::*;
+}
+
+#[cfg(not(feature = "parallel"))]
+pub mod prelude {
+    use std::iter::FlatMap;
+
+    pub trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Self::IntoIter {
+            self.into_iter()
+        }
+    }
+
+    impl<T: IntoIterator> IntoParallelIterator for T {}
+
+    pub trait IntoParallelRefIterator<'data> {
+        type Iter: Iterator;
+
+        fn par_iter(&'data self) -> Self::Iter;
+    }
+
+    impl<'data, T: 'data + ?Sized> IntoParallelRefIterator<'data> for T
+    where
+        &'data T: IntoIterator,
+    {
+        type Iter = <&'data T as IntoIterator>::IntoIter;
+
+        fn par_iter(&'data self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+
+    pub trait ParallelIterator: Iterator + Sized {
+        fn flat_map_iter<U, F>(self, map: F) -> FlatMap<Self, U, F>
+        where
+            U: IntoIterator,
+            F: FnMut(Self::Item) -> U,
+        {
+            self.flat_map(map)
+        }
+    }
+
+    impl<I: Iterator> ParallelIterator for I {}
+}
+
+/// Sizes the global thread pool, which has to happen before anything runs
+/// on it.
+#[cfg(feature = "parallel")]
+pub fn use_threads(threads: usize) -> Result<(), String> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .map_err(|error| format!("cannot start {} threads: {}", threads, error))
+}
@@ -0,0 +1,547 @@
+//! Recordings of simulations as they run. Solvers push the states of their
+//! [`ChristmasGraph`] into a [`Recorder`], which keeps every `stride`-th one
+//! as a frame of an asciinema `.cast` file or of a numbered PPM sequence.
+
+use std::{
+    env,
+    fs::{self, File},
+    io::{BufWriter, Seek, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{
+    extra::{self, ChristmasGraph},
+    image::{Image, Palette},
+    report::json_string,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// An asciicast v2 file, played back with `asciinema play`.
+    Cast,
+    /// One PPM image per frame, numbered from `frame-0000.ppm`.
+    Ppm,
+}
+
+/// Where and how recordings are made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub directory: PathBuf,
+    pub format: Format,
+    /// Frames per second of the cast. Image sequences leave the frame rate to
+    /// whatever turns them into a video.
+    pub fps: f64,
+    /// The number of states per frame.
+    pub stride: usize,
+    /// The most frames a recording has, the last of which is the final state.
+    pub max_frames: usize,
+    pub palette: Palette,
+    /// The size in pixels of the cells of image frames.
+    pub scale: u32,
+}
+
+impl Settings {
+    pub fn new(directory: PathBuf, format: Format) -> Self {
+        Self {
+            directory,
+            format,
+            fps: 10.0,
+            stride: 1,
+            max_frames: 1000,
+            palette: Palette::default(),
+            scale: 4,
+        }
+    }
+
+    /// Reads the settings from `RECORD`, the directory to record into, which
+    /// enables recording. `RECORD_FORMAT` is `cast` or `ppm`, and
+    /// `RECORD_FPS`, `RECORD_STRIDE`, `RECORD_FRAMES`, `RECORD_PALETTE` and
+    /// `RECORD_SCALE` override the other defaults.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let Some(directory) = env::var_os("RECORD").filter(|value| !value.is_empty()) else {
+            return Ok(None);
+        };
+
+        let format = match env::var("RECORD_FORMAT").as_deref() {
+            Ok("cast") | Err(_) => Format::Cast,
+            Ok("ppm") => Format::Ppm,
+            Ok(format) => {
+                return Err(format!(
+                    "invalid RECORD_FORMAT `{}`, expected `cast` or `ppm`",
+                    format
+                ))
+            }
+        };
+
+        let mut settings = Self::new(directory.into(), format);
+
+        fn positive<T>(name: &str) -> Result<Option<T>, String>
+        where
+            T: std::str::FromStr + PartialOrd + Default,
+        {
+            let Ok(value) = env::var(name) else {
+                return Ok(None);
+            };
+
+            match value.parse() {
+                Ok(number) if number > T::default() => Ok(Some(number)),
+                _ => Err(format!(
+                    "invalid {} `{}`, expected a positive number",
+                    name, value
+                )),
+            }
+        }
+
+        if let Some(fps) = positive("RECORD_FPS")? {
+            settings.fps = fps;
+        }
+
+        if let Some(stride) = positive("RECORD_STRIDE")? {
+            settings.stride = stride;
+        }
+
+        if let Some(max_frames) = positive("RECORD_FRAMES")? {
+            settings.max_frames = max_frames;
+        }
+
+        if let Some(scale) = positive("RECORD_SCALE")? {
+            settings.scale = scale;
+        }
+
+        if let Ok(palette) = env::var("RECORD_PALETTE") {
+            settings.palette = Palette::parse(&palette)?;
+        }
+
+        Ok(Some(settings))
+    }
+}
+
+/// The recording settings, read from the environment once. Invalid settings
+/// are reported and nothing is recorded.
+pub fn settings() -> Option<&'static Settings> {
+    static SETTINGS: OnceLock<Option<Settings>> = OnceLock::new();
+
+    SETTINGS
+        .get_or_init(|| {
+            Settings::from_env().unwrap_or_else(|error| {
+                eprintln!("warning: {}", error);
+                None
+            })
+        })
+        .as_ref()
+}
+
+enum Sink {
+    Cast {
+        file: BufWriter<File>,
+        path: PathBuf,
+        title: String,
+        width: usize,
+        height: usize,
+    },
+    /// The frames written so far, by the cell they start at and their size.
+    Ppm {
+        directory: PathBuf,
+        frames: Vec<(i64, i64, u32, u32)>,
+    },
+}
+
+/// Records the states of one part of a day. A recorder without settings,
+/// which is what [`Recorder::new`] gives when recording is off, ignores
+/// everything.
+pub struct Recorder {
+    day: u8,
+    part: u8,
+    settings: Option<Settings>,
+    states: usize,
+    frames: usize,
+    sink: Option<Sink>,
+    error: Option<String>,
+}
+
+impl Recorder {
+    pub fn new(day: u8, part: u8) -> Self {
+        Self::with_settings(day, part, settings().cloned())
+    }
+
+    pub fn with_settings(day: u8, part: u8, settings: Option<Settings>) -> Self {
+        Self {
+            day,
+            part,
+            settings,
+            states: 0,
+            frames: 0,
+            sink: None,
+            error: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.settings.is_some() && self.error.is_none()
+    }
+
+    /// Takes the next state of the simulation, starting with the initial
+    /// one, and keeps it as a frame if it is the first of its stride.
+    pub fn record<G: ChristmasGraph>(&mut self, graph: &G) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+
+        let captured = self.states.is_multiple_of(settings.stride);
+        self.states += 1;
+
+        // The last frame is kept for the final state
+        if captured && self.frames + 1 < settings.max_frames {
+            self.capture(graph);
+        }
+    }
+
+    /// Keeps the final state as the last frame and completes the recording,
+    /// reporting what went wrong, if anything.
+    pub fn finish<G: ChristmasGraph>(self, graph: &G) {
+        let (day, part) = (self.day, self.part);
+
+        if let Err(error) = self.close(graph) {
+            eprintln!(
+                "warning: cannot record day {} part {}: {}",
+                day, part, error
+            );
+        }
+    }
+
+    /// Keeps the final state as the last frame and completes the recording,
+    /// returning where it was written to.
+    pub fn close<G: ChristmasGraph>(mut self, graph: &G) -> Result<Option<PathBuf>, String> {
+        if self.settings.is_none() {
+            return Ok(None);
+        }
+
+        self.capture(graph);
+
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let Some(sink) = self.sink else {
+            return Ok(None);
+        };
+
+        match sink {
+            Sink::Cast {
+                file,
+                path,
+                title,
+                width,
+                height,
+            } => {
+                let mut file = file
+                    .into_inner()
+                    .map_err(|error| format!("cannot write {}: {}", path.display(), error))?;
+
+                // The header has a fixed length, so it can be rewritten with
+                // the size of the largest frame
+                file.rewind()
+                    .and_then(|_| file.write_all(cast_header(&title, width, height).as_bytes()))
+                    .map_err(|error| format!("cannot write {}: {}", path.display(), error))?;
+
+                Ok(Some(path))
+            }
+            Sink::Ppm { directory, frames } => {
+                align_frames(&directory, &frames, self.settings.as_ref().unwrap())?;
+
+                Ok(Some(directory))
+            }
+        }
+    }
+
+    fn capture<G: ChristmasGraph>(&mut self, graph: &G) {
+        if !self.is_recording() {
+            return;
+        }
+
+        if let Err(error) = self.write_frame(graph) {
+            self.error = Some(error);
+        }
+    }
+
+    fn write_frame<G: ChristmasGraph>(&mut self, graph: &G) -> Result<(), String> {
+        let settings = self.settings.as_ref().unwrap();
+        let name = format!("day{}-part{}", self.day, self.part);
+
+        let sink = match &mut self.sink {
+            Some(sink) => sink,
+            None => self
+                .sink
+                .insert(open(settings, &name, self.day, self.part)?),
+        };
+
+        match sink {
+            Sink::Cast {
+                file,
+                path,
+                width,
+                height,
+                ..
+            } => {
+                let frame = format!("{:#}", graph.view(*extra::viewport()));
+
+                *width = frame.lines().map(visible_width).fold(*width, usize::max);
+                *height = (*height).max(frame.lines().count());
+
+                let time = self.frames as f64 / settings.fps;
+                let data = format!("\x1b[H\x1b[2J{}", frame.replace('\n', "\r\n"));
+
+                writeln!(file, "[{:.3}, \"o\", {}]", time, json_string(&data))
+                    .map_err(|error| format!("cannot write {}: {}", path.display(), error))?;
+            }
+            Sink::Ppm { directory, frames } => {
+                let metadata = graph.as_graph_metadata();
+                let path = directory.join(frame_name(frames.len()));
+
+                graph
+                    .to_image(&settings.palette, settings.scale)
+                    .save(&path)?;
+                frames.push((metadata.x, metadata.y, metadata.width, metadata.height));
+            }
+        }
+
+        self.frames += 1;
+
+        Ok(())
+    }
+}
+
+fn open(settings: &Settings, name: &str, day: u8, part: u8) -> Result<Sink, String> {
+    let create = |directory: &Path| {
+        fs::create_dir_all(directory)
+            .map_err(|error| format!("cannot create {}: {}", directory.display(), error))
+    };
+
+    match settings.format {
+        Format::Cast => {
+            create(&settings.directory)?;
+
+            let path = settings.directory.join(format!("{}.cast", name));
+            let title = format!("Day {} part {}", day, part);
+            let mut file = File::create(&path)
+                .map(BufWriter::new)
+                .map_err(|error| format!("cannot create {}: {}", path.display(), error))?;
+
+            file.write_all(cast_header(&title, 0, 0).as_bytes())
+                .map_err(|error| format!("cannot write {}: {}", path.display(), error))?;
+
+            Ok(Sink::Cast {
+                file,
+                path,
+                title,
+                width: 0,
+                height: 0,
+            })
+        }
+        Format::Ppm => {
+            let directory = settings.directory.join(name);
+            create(&directory)?;
+
+            Ok(Sink::Ppm {
+                directory,
+                frames: vec![],
+            })
+        }
+    }
+}
+
+fn frame_name(index: usize) -> String {
+    format!("frame-{:04}.ppm", index)
+}
+
+/// Graphs that grow or move give frames of their own sizes, which are drawn
+/// again on the area covering all of them so they line up.
+fn align_frames(
+    directory: &Path,
+    frames: &[(i64, i64, u32, u32)],
+    settings: &Settings,
+) -> Result<(), String> {
+    let Some(first) = frames.first() else {
+        return Ok(());
+    };
+
+    if frames.iter().all(|frame| frame == first) {
+        return Ok(());
+    }
+
+    let min_x = frames.iter().map(|(x, ..)| *x).min().unwrap();
+    let min_y = frames.iter().map(|(_, y, ..)| *y).min().unwrap();
+    let max_x = frames
+        .iter()
+        .map(|(x, _, w, _)| x + *w as i64)
+        .max()
+        .unwrap();
+    let max_y = frames
+        .iter()
+        .map(|(_, y, _, h)| y + *h as i64)
+        .max()
+        .unwrap();
+    let scale = settings.scale.max(1);
+
+    for (index, (x, y, ..)) in frames.iter().enumerate() {
+        let path = directory.join(frame_name(index));
+        let bytes = fs::read(&path)
+            .map_err(|error| format!("cannot read {}: {}", path.display(), error))?;
+        let frame =
+            Image::from_ppm(&bytes).map_err(|error| format!("{}: {}", path.display(), error))?;
+
+        let mut image = Image::new(
+            (max_x - min_x) as u32 * scale,
+            (max_y - min_y) as u32 * scale,
+            settings.palette.background,
+        );
+        image.paste(
+            (x - min_x) as u32 * scale,
+            (y - min_y) as u32 * scale,
+            &frame,
+        );
+        image.save(&path)?;
+    }
+
+    Ok(())
+}
+
+/// The first line of a cast, which is always as long so it can be replaced.
+fn cast_header(title: &str, width: usize, height: usize) -> String {
+    format!(
+        "{{\"version\": 2, \"width\": {:<10}, \"height\": {:<10}, \"title\": {}}}\n",
+        width.max(1),
+        height.max(1),
+        json_string(title)
+    )
+}
+
+/// The number of columns a line takes on a terminal, without its escapes.
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\x1b' => {
+                chars.find(char::is_ascii_alphabetic);
+            }
+            _ => width += 1,
+        }
+    }
+
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::{cast_header, json_string, visible_width, Format, Recorder, Settings};
+    use crate::{
+        extra::{ChristmasGraph, GraphMetadata},
+        image::Image,
+    };
+
+    /// A single cell at `x`, to record a graph that moves.
+    struct Step(i64);
+
+    impl ChristmasGraph for Step {
+        fn as_graph_metadata(&self) -> GraphMetadata {
+            GraphMetadata {
+                x: self.0,
+                y: 0,
+                width: 1,
+                height: 1,
+                legend_step_x: 1,
+                legend_step_y: 1,
+                legend_y_width: 1,
+                legend_x_width: 1,
+            }
+        }
+
+        fn graph_legend_x(&self, value: i64) -> Vec<char> {
+            value.to_string().chars().collect()
+        }
+
+        fn graph_legend_y(&self, value: i64) -> Vec<char> {
+            value.to_string().chars().collect()
+        }
+
+        fn graph_value(&self, _x: i64, _y: i64) -> Option<char> {
+            Some('#')
+        }
+    }
+
+    fn settings(name: &str, format: Format) -> Settings {
+        let directory = env::temp_dir().join(format!("aoc2022-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        Settings {
+            stride: 2,
+            max_frames: 3,
+            scale: 1,
+            ..Settings::new(directory, format)
+        }
+    }
+
+    #[test]
+    fn records_casts() {
+        let settings = settings("cast", Format::Cast);
+        let mut recorder = Recorder::with_settings(14, 1, Some(settings.clone()));
+
+        for x in 0..10 {
+            recorder.record(&Step(x));
+        }
+
+        let path = recorder.close(&Step(10)).unwrap().unwrap();
+        let cast = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+
+        // States 0 and 2 from their strides, and the final one
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], cast_header("Day 14 part 1", 5, 3).trim_end());
+        assert_eq!(
+            lines[1],
+            "[0.000, \"o\", \"\\u001b[H\\u001b[2J  \\u001b[2m0\\u001b[0m  \\r\\n\\u001b[2m0\\u001b[0m #\\r\\n\"]"
+        );
+        assert!(lines[2].starts_with("[0.100, \"o\", \"\\u001b[H\\u001b[2J  \\u001b[2m2"));
+        assert!(lines[3].starts_with(
+            "[0.200, \"o\", \"\\u001b[H\\u001b[2J  \\u001b[2m1\\u001b[0m  \\r\\n  \\u001b[2m0"
+        ));
+
+        fs::remove_dir_all(&settings.directory).unwrap();
+    }
+
+    #[test]
+    fn records_aligned_images() {
+        let settings = settings("ppm", Format::Ppm);
+        let mut recorder = Recorder::with_settings(9, 2, Some(settings.clone()));
+
+        recorder.record(&Step(0));
+        let directory = recorder.close(&Step(2)).unwrap().unwrap();
+
+        let frame = |index: usize| {
+            Image::from_ppm(&fs::read(directory.join(format!("frame-{:04}.ppm", index))).unwrap())
+                .unwrap()
+        };
+        let (background, foreground) = (settings.palette.background, settings.palette.foreground);
+
+        assert_eq!(frame(0).pixels, [foreground, background, background]);
+        assert_eq!(frame(1).pixels, [background, background, foreground]);
+        assert!(!directory.join("frame-0002.ppm").exists());
+
+        fs::remove_dir_all(&settings.directory).unwrap();
+    }
+
+    #[test]
+    fn escapes_casts() {
+        assert_eq!(
+            json_string("a \"b\"\\\n\x1b"),
+            "\"a \\\"b\\\"\\\\\\n\\u001b\""
+        );
+        assert_eq!(visible_width("\x1b[1;31m#\x1b[0m ."), 3);
+        assert!(Recorder::with_settings(1, 1, None).close(&Step(0)) == Ok(None));
+    }
+}
@@ -0,0 +1,551 @@
+use std::{fmt::Write, time::Duration};
+
+use crate::{
+    answers::{self, Answer, Verdict},
+    error::SolveError,
+};
+
+pub const PRINT_WIDTH: usize = 80;
+pub const TIME_PAD_WIDTH: usize = 15;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved(Answer),
+    Skipped,
+    NotImplemented,
+    Failed(String),
+    InvalidInput(SolveError),
+    Panicked(String),
+    TimedOut,
+}
+
+impl Outcome {
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Solved(_) => "ok",
+            Outcome::Skipped | Outcome::NotImplemented => "skipped",
+            Outcome::Failed(_) | Outcome::InvalidInput(_) | Outcome::Panicked(_) => "failed",
+            Outcome::TimedOut => "timeout",
+        }
+    }
+
+    pub fn answer(&self) -> Option<&Answer> {
+        match self {
+            Outcome::Solved(answer) => Some(answer),
+            _ => None,
+        }
+    }
+
+    pub fn message(&self) -> Option<String> {
+        match self {
+            Outcome::Solved(_) => None,
+            Outcome::Skipped => Some("not selected".to_string()),
+            Outcome::NotImplemented => Some("not implemented".to_string()),
+            Outcome::Failed(error) | Outcome::Panicked(error) => Some(error.clone()),
+            Outcome::InvalidInput(error) => Some(error.to_string()),
+            Outcome::TimedOut => Some("exceeded the time budget".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    /// Time spent parsing the day's input, shared by both parts.
+    pub parse: Option<Duration>,
+    pub duration: Duration,
+    pub outcome: Outcome,
+    pub verdict: Option<Verdict>,
+}
+
+impl Report {
+    pub fn new(day: u8, part: u8, duration: Duration, outcome: Outcome) -> Self {
+        Self {
+            day,
+            part,
+            parse: None,
+            duration,
+            outcome,
+            verdict: None,
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self.outcome,
+            Outcome::Failed(_)
+                | Outcome::InvalidInput(_)
+                | Outcome::Panicked(_)
+                | Outcome::TimedOut
+        ) || matches!(self.verdict, Some(Verdict::Wrong(_)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Human,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "invalid format `{}`, expected human, json or csv",
+                value
+            )),
+        }
+    }
+}
+
+pub struct Summary {
+    pub reports: Vec<Report>,
+    pub thread_time: Duration,
+    pub real_time: Duration,
+}
+
+impl Summary {
+    pub fn new(mut reports: Vec<Report>, real_time: Duration) -> Self {
+        reports.sort_by_key(|report| (report.day, report.part));
+
+        let solve_time: Duration = reports.iter().map(|report| report.duration).sum();
+        let parse_time: Duration = parse_times(&reports).map(|(_, parse)| parse).sum();
+        let thread_time = solve_time + parse_time;
+
+        Self {
+            reports,
+            thread_time,
+            real_time,
+        }
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.reports.iter().any(Report::is_failure)
+    }
+
+    /// Diffs of every answer that does not match the expected one.
+    pub fn mismatches(&self) -> String {
+        let mut output = String::new();
+
+        for report in &self.reports {
+            if let (Outcome::Solved(answer), Some(Verdict::Wrong(expected))) =
+                (&report.outcome, &report.verdict)
+            {
+                writeln!(output, "day{}pt{}: wrong answer", report.day, report.part).unwrap();
+                output += &answers::diff(expected, &answer.to_string());
+            }
+        }
+
+        output
+    }
+
+    /// Points out where the input of each day that failed to parse is malformed.
+    pub fn diagnostics(&self) -> String {
+        let mut output = String::new();
+        let mut reported = vec![];
+
+        for report in &self.reports {
+            if let Outcome::InvalidInput(error) = &report.outcome {
+                if !reported.contains(&report.day) {
+                    reported.push(report.day);
+                    output += &error.diagnostic();
+                }
+            }
+        }
+
+        output
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Human => self.human(),
+            Format::Json => self.json(),
+            Format::Csv => self.csv(),
+        }
+    }
+
+    fn human(&self) -> String {
+        let mut output = String::new();
+        let mut parse_times = parse_times(&self.reports).peekable();
+
+        for report in &self.reports {
+            if let Some((_, parse)) = parse_times.next_if(|(day, _)| *day == report.day) {
+                output += &display_with_duration(
+                    PRINT_WIDTH,
+                    TIME_PAD_WIDTH,
+                    false,
+                    format!("day{} parse: ", report.day).as_str(),
+                    "-",
+                    "",
+                    parse,
+                );
+                output += "\n";
+            }
+
+            let content = match &report.outcome {
+                Outcome::Solved(answer) => answer.to_string(),
+                Outcome::Skipped => "SKIPPED".to_string(),
+                Outcome::NotImplemented => "NOT IMPLEMENTED".to_string(),
+                Outcome::Failed(error) => format!("ERROR: {}", error),
+                // The full diagnostic is printed after the results
+                Outcome::InvalidInput(error) if error.line == 0 => "INVALID INPUT".to_string(),
+                Outcome::InvalidInput(error) => {
+                    format!(
+                        "INVALID INPUT: line {}, column {}",
+                        error.line, error.column
+                    )
+                }
+                Outcome::Panicked(message) => format!("FAILED: {}", message),
+                Outcome::TimedOut => "TIMEOUT".to_string(),
+            };
+
+            let multiline = report.outcome.answer().is_some_and(Answer::is_multiline);
+
+            let suffix = match &report.verdict {
+                None => "-",
+                Some(Verdict::Correct) => "ok",
+                Some(Verdict::Wrong(_)) => "WRONG",
+                Some(Verdict::Unknown) => "?",
+            };
+
+            let line = display_with_duration(
+                PRINT_WIDTH,
+                TIME_PAD_WIDTH,
+                multiline,
+                format!("day{}pt{}: ", report.day, report.part).as_str(),
+                suffix,
+                content.as_str(),
+                report.duration,
+            );
+
+            output += &line;
+            output += "\n";
+        }
+
+        output += "\n";
+
+        for (label, duration) in [
+            ("total thread time", self.thread_time),
+            ("real time", self.real_time),
+        ] {
+            output += &display_with_duration(
+                PRINT_WIDTH,
+                TIME_PAD_WIDTH,
+                false,
+                "> ",
+                "-",
+                label,
+                duration,
+            );
+            output += "\n";
+        }
+
+        output
+    }
+
+    fn json(&self) -> String {
+        let mut output = String::new();
+
+        output += "{\n  \"results\": [";
+
+        for (index, report) in self.reports.iter().enumerate() {
+            if index > 0 {
+                output += ",";
+            }
+
+            write!(
+                output,
+                "\n    {{\"day\": {}, \"part\": {}, \"answer\": {}, \"parse_ns\": {}, \"duration_ns\": {}, \"status\": \"{}\", \"message\": {}, \"verdict\": {}}}",
+                report.day,
+                report.part,
+                report.outcome.answer().map_or("null".to_string(), json_answer),
+                report
+                    .parse
+                    .map_or("null".to_string(), |parse| parse.as_nanos().to_string()),
+                report.duration.as_nanos(),
+                report.outcome.status(),
+                json_option(report.outcome.message().as_deref()),
+                json_option(report.verdict.as_ref().map(Verdict::name)),
+            )
+            .unwrap();
+        }
+
+        write!(
+            output,
+            "\n  ],\n  \"total_thread_time_ns\": {},\n  \"real_time_ns\": {}\n}}\n",
+            self.thread_time.as_nanos(),
+            self.real_time.as_nanos()
+        )
+        .unwrap();
+
+        output
+    }
+
+    fn csv(&self) -> String {
+        let mut output =
+            String::from("day,part,answer,parse_ns,duration_ns,status,message,verdict\r\n");
+
+        for report in &self.reports {
+            write!(
+                output,
+                "{},{},{},{},{},{},{},{}\r\n",
+                report.day,
+                report.part,
+                csv_field(
+                    &report
+                        .outcome
+                        .answer()
+                        .map_or(String::new(), Answer::to_string)
+                ),
+                report
+                    .parse
+                    .map_or(String::new(), |parse| parse.as_nanos().to_string()),
+                report.duration.as_nanos(),
+                report.outcome.status(),
+                csv_field(report.outcome.message().as_deref().unwrap_or("")),
+                report.verdict.as_ref().map_or("", Verdict::name),
+            )
+            .unwrap();
+        }
+
+        for (label, duration) in [
+            ("total thread time", self.thread_time),
+            ("real time", self.real_time),
+        ] {
+            write!(output, "total,,{},,{},,,\r\n", label, duration.as_nanos()).unwrap();
+        }
+
+        output
+    }
+}
+
+/// The parse time of every parsed day, once per day.
+fn parse_times(reports: &[Report]) -> impl Iterator<Item = (u8, Duration)> + '_ {
+    let mut last_day = None;
+
+    reports.iter().filter_map(move |report| {
+        let parse = report.parse?;
+
+        if last_day == Some(report.day) {
+            return None;
+        }
+
+        last_day = Some(report.day);
+
+        Some((report.day, parse))
+    })
+}
+
+pub fn display_with_duration(
+    target_len: usize,
+    time_len: usize,
+    multiline: bool,
+    prefix: &str,
+    suffix: &str,
+    content: &str,
+    duration: Duration,
+) -> String {
+    let mut output = String::new();
+    output += prefix;
+    if multiline {
+        output += "\n";
+    }
+    let duration_str = format!("{:?}", duration);
+    let content_len = content.lines().map(|line| line.len()).max().unwrap_or(0);
+    let pad_len = target_len
+        .saturating_sub(if !multiline { prefix.len() } else { 0 })
+        .saturating_sub(content_len)
+        .saturating_sub(suffix.len());
+
+    let main_pad = " ".repeat(pad_len.saturating_sub(time_len));
+    let time_pad = " ".repeat(time_len.saturating_sub(duration_str.len()));
+    output += format!(
+        "{}{}{}{}{}",
+        content, main_pad, suffix, time_pad, duration_str
+    )
+    .as_str();
+
+    output
+}
+
+/// Integers become numbers and grids arrays of rows, so that consumers need
+/// not parse them again.
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Integer(value) => value.to_string(),
+        Answer::Text(text) => json_string(text),
+        Answer::Grid(grid) => {
+            let rows: Vec<String> = grid.rows().map(|row| json_string(&row)).collect();
+
+            format!("[{}]", rows.join(", "))
+        }
+    }
+}
+
+fn json_option(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), json_string)
+}
+
+pub(crate) fn json_string(value: &str) -> String {
+    let mut output = String::from("\"");
+
+    for char in value.chars() {
+        match char {
+            '"' => output += "\\\"",
+            '\\' => output += "\\\\",
+            '\n' => output += "\\n",
+            '\r' => output += "\\r",
+            '\t' => output += "\\t",
+            char if char.is_control() => write!(output, "\\u{:04x}", char as u32).unwrap(),
+            char => output.push(char),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+/// Quotes a field when needed, keeping line breaks inside the quotes as
+/// RFC 4180 allows.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{csv_field, json_string, Format, Outcome, Report, Summary};
+    use crate::{
+        answers::{Answer, Grid, Verdict},
+        error::SolveError,
+    };
+
+    fn summary() -> Summary {
+        let mut solved = Report::new(
+            1,
+            1,
+            Duration::from_nanos(1000),
+            Outcome::Solved(Answer::from(24000u64)),
+        );
+        solved.verdict = Some(Verdict::Wrong("24001".to_string()));
+        solved.parse = Some(Duration::from_nanos(100));
+
+        let mut second = Report::new(
+            1,
+            2,
+            Duration::from_nanos(50),
+            Outcome::Solved(Answer::from("45000")),
+        );
+        second.parse = Some(Duration::from_nanos(100));
+
+        Summary::new(
+            vec![
+                second,
+                Report::new(
+                    10,
+                    2,
+                    Duration::from_nanos(250),
+                    Outcome::Solved(Answer::from(Grid::from("#..#\n.##."))),
+                ),
+                solved,
+                Report::new(16, 2, Duration::from_nanos(500), Outcome::TimedOut),
+                Report::new(22, 1, Duration::ZERO, Outcome::NotImplemented),
+            ],
+            Duration::from_nanos(2000),
+        )
+    }
+
+    #[test]
+    fn json_escapes_strings() {
+        assert_eq!(json_string("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+        assert_eq!(json_string("\u{1}"), r#""\u0001""#);
+    }
+
+    #[test]
+    fn csv_quotes_fields() {
+        assert_eq!(csv_field("24000"), "24000");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\"\n"), "\"say \"\"hi\"\"\n\"");
+    }
+
+    #[test]
+    fn render_machine_formats() {
+        let summary = summary();
+
+        assert_eq!(
+            summary.render(Format::Json),
+            r##"{
+  "results": [
+    {"day": 1, "part": 1, "answer": 24000, "parse_ns": 100, "duration_ns": 1000, "status": "ok", "message": null, "verdict": "wrong"},
+    {"day": 1, "part": 2, "answer": "45000", "parse_ns": 100, "duration_ns": 50, "status": "ok", "message": null, "verdict": null},
+    {"day": 10, "part": 2, "answer": ["#..#", ".##."], "parse_ns": null, "duration_ns": 250, "status": "ok", "message": null, "verdict": null},
+    {"day": 16, "part": 2, "answer": null, "parse_ns": null, "duration_ns": 500, "status": "timeout", "message": "exceeded the time budget", "verdict": null},
+    {"day": 22, "part": 1, "answer": null, "parse_ns": null, "duration_ns": 0, "status": "skipped", "message": "not implemented", "verdict": null}
+  ],
+  "total_thread_time_ns": 1900,
+  "real_time_ns": 2000
+}
+"##
+        );
+
+        assert_eq!(
+            summary.render(Format::Csv),
+            "day,part,answer,parse_ns,duration_ns,status,message,verdict\r\n\
+             1,1,24000,100,1000,ok,,wrong\r\n\
+             1,2,45000,100,50,ok,,\r\n\
+             10,2,\"#..#\n.##.\",,250,ok,,\r\n\
+             16,2,,,500,timeout,exceeded the time budget,\r\n\
+             22,1,,,0,skipped,not implemented,\r\n\
+             total,,total thread time,,1900,,,\r\n\
+             total,,real time,,2000,,,\r\n"
+        );
+    }
+
+    #[test]
+    fn wrong_answers_are_failures() {
+        let summary = summary();
+
+        assert!(summary.has_failures());
+        assert!(
+            Report::new(5, 1, Duration::ZERO, Outcome::Panicked("oops".to_string())).is_failure()
+        );
+        assert_eq!(
+            summary.mismatches(),
+            "day1pt1: wrong answer\n- 24001\n+ 24000\n"
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_diagnosed_once_per_day() {
+        let input = "1\nx\n";
+        let error = SolveError::new(&input[2..3], "a number").locate(20, input);
+        let reports = (1..=2)
+            .map(|part| {
+                Report::new(
+                    20,
+                    part,
+                    Duration::ZERO,
+                    Outcome::InvalidInput(error.clone()),
+                )
+            })
+            .collect();
+        let summary = Summary::new(reports, Duration::ZERO);
+
+        assert!(summary.has_failures());
+        assert_eq!(summary.diagnostics(), error.diagnostic());
+        assert!(summary
+            .human()
+            .contains("day20pt2: INVALID INPUT: line 2, column 1"));
+    }
+}
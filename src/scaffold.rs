@@ -0,0 +1,283 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::input::{EXAMPLE_DIR, INPUT_DIR};
+
+const LIB: &str = "src/lib.rs";
+const SOLVER: &str = "src/solver.rs";
+const CASES: &str = "example_input/cases.txt";
+
+/// rustfmt's default line width, which the solver imports are wrapped at.
+const MAX_WIDTH: usize = 100;
+
+fn template(day: u8) -> String {
+    format!(
+        r#"use crate::{{answers::Answer, error::SolveError, solver::solution}};
+
+solution!({day}, "Day {day}");
+
+pub fn parse(input: &str) -> Result<Vec<String>, SolveError> {{
+    Ok(input.lines().map(String::from).collect())
+}}
+
+pub fn solve_1(_lines: &[String]) -> Answer {{
+    todo!("solve day {day} part 1")
+}}
+
+pub fn solve_2(_lines: &[String]) -> Answer {{
+    todo!("solve day {day} part 2")
+}}
+"#
+    )
+}
+
+/// Creates the module and empty inputs of a new day within the repository
+/// at `root`, and registers it with the library, the solvers and the example
+/// cases. Every change is prepared before the first file is written, and
+/// existing files are never overwritten. Returns the files written.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    let module = root.join(format!("src/day{}.rs", day));
+    let input = root.join(INPUT_DIR).join(format!("day{}.txt", day));
+    let example = root.join(EXAMPLE_DIR).join(format!("day{}.txt", day));
+
+    for path in [&module, &input, &example] {
+        if path.exists() {
+            return Err(format!(
+                "{} already exists, refusing to overwrite it",
+                path.display()
+            ));
+        }
+    }
+
+    let read = |path: &str| {
+        fs::read_to_string(root.join(path))
+            .map_err(|error| format!("cannot read {}: {}", root.join(path).display(), error))
+    };
+
+    let updates = [
+        (LIB, register_module(&read(LIB)?, day)?),
+        (SOLVER, register_solver(&read(SOLVER)?, day)?),
+        (CASES, add_cases(&read(CASES)?, day)),
+    ];
+
+    let mut written = vec![];
+    let mut write = |path: PathBuf, content: &str| {
+        fs::write(&path, content)
+            .map_err(|error| format!("cannot write {}: {}", path.display(), error))?;
+        written.push(path);
+        Ok::<_, String>(())
+    };
+
+    write(module, &template(day))?;
+    write(input, "")?;
+    write(example, "")?;
+
+    for (path, content) in updates {
+        write(root.join(path), &content)?;
+    }
+
+    Ok(written)
+}
+
+/// Adds `pub mod dayN;` to the library, keeping the declarations sorted the
+/// way rustfmt sorts them.
+fn register_module(lib: &str, day: u8) -> Result<String, String> {
+    let name = format!("day{}", day);
+    let declaration = format!("pub mod {};", name);
+    let mut lines: Vec<&str> = lib.lines().collect();
+
+    if lines.contains(&declaration.as_str()) {
+        return Err(format!("day {} is already declared in {}", day, LIB));
+    }
+
+    let days: Vec<usize> = (0..lines.len())
+        .filter(|index| lines[*index].starts_with("pub mod day"))
+        .collect();
+    let last = days
+        .last()
+        .ok_or_else(|| format!("no day modules declared in {}", LIB))?;
+
+    let index = days
+        .iter()
+        .copied()
+        .find(|index| lines[*index]["pub mod ".len()..].trim_end_matches(';') > name.as_str())
+        .unwrap_or(last + 1);
+
+    lines.insert(index, &declaration);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Imports the new day into the solver registry and lists its solution in
+/// [`SOLVERS`](crate::solver::SOLVERS), ordered by day.
+fn register_solver(solver: &str, day: u8) -> Result<String, String> {
+    let missing = || format!("cannot find the solver imports in {}", SOLVER);
+
+    let start = solver.find("use crate::{\n").ok_or_else(missing)?;
+    let items_start = start + "use crate::{\n".len();
+    let items_end = items_start + solver[items_start..].find("};\n").ok_or_else(missing)?;
+
+    let name = format!("day{}", day);
+    let mut items: Vec<&str> = solver[items_start..items_end]
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect();
+
+    if items.contains(&name.as_str()) {
+        return Err(format!("day {} is already registered in {}", day, SOLVER));
+    }
+
+    // Days sort between the other imports, like rustfmt sorts them
+    let index = items
+        .iter()
+        .position(|item| *item > name.as_str())
+        .unwrap_or(items.len());
+    items.insert(index, &name);
+
+    let mut imports = String::new();
+    let mut line = String::new();
+
+    for item in items {
+        if !line.is_empty() && 4 + line.len() + 1 + item.len() + 1 > MAX_WIDTH {
+            imports += &format!("    {}\n", line);
+            line.clear();
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+
+        line += item;
+        line.push(',');
+    }
+
+    imports += &format!("    {}\n", line);
+
+    let solver = format!(
+        "{}{}{}",
+        &solver[..items_start],
+        imports,
+        &solver[items_end..]
+    );
+
+    // The registry lists the solutions by day
+    let entry = format!("    &day{}::Solution,", day);
+    let mut lines: Vec<&str> = solver.lines().collect();
+
+    let entries: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let day = line.strip_prefix("    &day")?.strip_suffix("::Solution,")?;
+            Some((index, day.parse().ok()?))
+        })
+        .collect();
+
+    let (last, _) = entries
+        .last()
+        .ok_or_else(|| format!("cannot find SOLVERS in {}", SOLVER))?;
+
+    let index = entries
+        .iter()
+        .find(|(_, listed)| *listed > day)
+        .map_or(last + 1, |(index, _)| *index);
+
+    lines.insert(index, &entry);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Lists both parts of the new example with an answer still to be filled in.
+fn add_cases(cases: &str, day: u8) -> String {
+    let mut cases = cases.to_string();
+
+    if !cases.is_empty() && !cases.ends_with('\n') {
+        cases.push('\n');
+    }
+
+    for part in 1..=2 {
+        cases += &format!("{:<16}{:<6}?\n", format!("day{}.txt", day), part);
+    }
+
+    cases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{register_module, register_solver};
+
+    fn lines(lines: &[&str]) -> String {
+        lines.join("\n") + "\n"
+    }
+
+    #[test]
+    fn registers_days_in_order() {
+        let lib = lines(&[
+            "pub mod day1;",
+            "pub mod day10;",
+            "pub mod day3;",
+            "",
+            "pub mod error;",
+        ]);
+
+        assert_eq!(
+            register_module(&lib, 2),
+            Ok(lines(&[
+                "pub mod day1;",
+                "pub mod day10;",
+                "pub mod day2;",
+                "pub mod day3;",
+                "",
+                "pub mod error;"
+            ]))
+        );
+        assert!(register_module(&lib, 3).is_err());
+
+        let solver = lines(&[
+            "use crate::{",
+            "    answers::Answer, day1, day10, day3,",
+            "    error::SolveError,",
+            "};",
+            "",
+            "pub static SOLVERS: &[&dyn Solver] = &[",
+            "    &day1::Solution,",
+            "    &day3::Solution,",
+            "    &day10::Solution,",
+            "];",
+        ]);
+
+        assert_eq!(
+            register_solver(&solver, 4),
+            Ok(lines(&[
+                "use crate::{",
+                "    answers::Answer, day1, day10, day3, day4, error::SolveError,",
+                "};",
+                "",
+                "pub static SOLVERS: &[&dyn Solver] = &[",
+                "    &day1::Solution,",
+                "    &day3::Solution,",
+                "    &day4::Solution,",
+                "    &day10::Solution,",
+                "];",
+            ]))
+        );
+        assert!(register_solver(&solver, 10).is_err());
+    }
+
+    #[test]
+    fn registers_like_rustfmt() {
+        // Taking a day out and registering it again gives the formatted files
+        let lib = include_str!("lib.rs");
+        let without = lib.replacen("pub mod day25;\n", "", 1);
+        assert_eq!(register_module(&without, 25).as_deref(), Ok(lib));
+
+        let solver = include_str!("solver.rs");
+        let without = solver
+            .replacen(" day25,", "", 1)
+            .replacen("    &day25::Solution,\n", "", 1);
+        assert_eq!(register_solver(&without, 25).as_deref(), Ok(solver));
+    }
+}
@@ -0,0 +1,463 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answers::Answer, day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19,
+    day2, day20, day21, day22, day23, day24, day25, day3, day4, day5, day6, day7, day8, day9,
+    error::SolveError,
+};
+
+pub const DAYS: u8 = 25;
+pub const PARTS: u8 = 2;
+
+/// The days and parts picked for a run. `explicit` is set when the user
+/// narrowed the run down instead of running everything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub days: Vec<u8>,
+    pub part: Option<u8>,
+    pub explicit: bool,
+}
+
+impl Selection {
+    pub fn contains(&self, day: u8, part: u8) -> bool {
+        self.days.contains(&day) && self.part.is_none_or(|selected| selected == part)
+    }
+}
+
+/// Cooperative cancellation for long running solvers, which poll the token
+/// in their search loops and return early once it is cancelled. The runner
+/// discards whatever a cancelled solve returns. Polling only reads the
+/// clock, so it is cheap enough for the innermost loops.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel {
+    deadline: Option<Instant>,
+}
+
+impl Cancel {
+    pub fn never() -> Self {
+        Self::default()
+    }
+
+    /// A token that cancels itself once `budget` has passed.
+    pub fn after(budget: Duration) -> Self {
+        Self {
+            // Budgets too large for the clock never run out
+            deadline: Instant::now().checked_add(budget),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// A parsed puzzle input. Each day parses into its own type, which the
+/// `solution!` macro recovers before calling the solve functions.
+pub type Puzzle = dyn Any + Send + Sync;
+
+pub trait Solver: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn parse(&self, input: &str) -> Result<Box<Puzzle>, SolveError>;
+    fn solve_1(&self, puzzle: &Puzzle, cancel: &Cancel) -> Result<Answer, SolveError>;
+    fn solve_2(&self, puzzle: &Puzzle, cancel: &Cancel) -> Result<Answer, SolveError>;
+
+    /// Overrides a parameter of the parsed puzzle which the examples choose
+    /// differently from the real puzzles, like the row day 15 asks about.
+    fn configure(&self, puzzle: &mut Puzzle, name: &str, value: &str) -> Result<(), String>;
+
+    /// Solves one part. Errors point at input that only this part cannot
+    /// use, and still have to be located like parse errors.
+    fn solve(&self, part: u8, puzzle: &Puzzle, cancel: &Cancel) -> Result<Answer, SolveError> {
+        match part {
+            1 => self.solve_1(puzzle, cancel),
+            2 => self.solve_2(puzzle, cancel),
+            _ => unreachable!("puzzles only have two parts"),
+        }
+    }
+}
+
+/// What the solve functions of a day return, either the answer or, for
+/// input that only one of the parts cannot use, an error.
+pub trait Solved {
+    fn into_result(self) -> Result<Answer, SolveError>;
+}
+
+impl Solved for Answer {
+    fn into_result(self) -> Result<Answer, SolveError> {
+        Ok(self)
+    }
+}
+
+impl Solved for Result<Answer, SolveError> {
+    fn into_result(self) -> Result<Answer, SolveError> {
+        self
+    }
+}
+
+/// Recovers the puzzle produced by `parse`, which is only passed along to
+/// name the puzzle type.
+pub fn downcast<T: 'static>(_parse: fn(&str) -> Result<T, SolveError>, puzzle: &Puzzle) -> &T {
+    puzzle.downcast_ref().expect("puzzle parsed by another day")
+}
+
+/// Like [`downcast`], for puzzles that are about to be configured.
+pub fn downcast_mut<T: 'static>(
+    _parse: fn(&str) -> Result<T, SolveError>,
+    puzzle: &mut Puzzle,
+) -> &mut T {
+    puzzle.downcast_mut().expect("puzzle parsed by another day")
+}
+
+/// Declares a `Solution` for the calling day module, forwarding to its
+/// `parse`, `solve_1` and `solve_2` functions. Parse errors are located in
+/// the input before they are returned. The solve functions borrow the parsed
+/// puzzle and return anything [`Solved`], and days marked `cancellable` also take the [`Cancel`]
+/// token. Days marked `configurable` forward [`Solver::configure`] to their
+/// `configure` function. The solution still has to be listed in [`SOLVERS`]
+/// to be picked up by the runner.
+macro_rules! solution {
+    ($day:literal, $title:literal) => {
+        $crate::solver::solution!(@impl $day, $title, |puzzle, _cancel| (puzzle), unconfigurable);
+    };
+    ($day:literal, $title:literal, cancellable) => {
+        $crate::solver::solution!(@impl $day, $title, |puzzle, cancel| (puzzle, cancel), unconfigurable);
+    };
+    ($day:literal, $title:literal, configurable) => {
+        $crate::solver::solution!(@impl $day, $title, |puzzle, _cancel| (puzzle), configurable);
+    };
+    (@configure $day:literal, $puzzle:ident, $name:ident, $value:ident, unconfigurable) => {{
+        let _ = ($puzzle, $value);
+        Err(format!("day {} has no parameter `{}`", $day, $name))
+    }};
+    (@configure $day:literal, $puzzle:ident, $name:ident, $value:ident, configurable) => {
+        configure($crate::solver::downcast_mut(parse, $puzzle), $name, $value)
+    };
+    (
+        @impl $day:literal,
+        $title:literal,
+        |$puzzle:ident, $cancel:ident| $args:tt,
+        $configurable:ident
+    ) => {
+        pub struct Solution;
+
+        impl $crate::solver::Solver for Solution {
+            fn day(&self) -> u8 {
+                $day
+            }
+
+            fn title(&self) -> &'static str {
+                $title
+            }
+
+            fn parse(
+                &self,
+                input: &str,
+            ) -> Result<Box<$crate::solver::Puzzle>, $crate::error::SolveError> {
+                match parse(input) {
+                    Ok(puzzle) => Ok(Box::new(puzzle)),
+                    Err(error) => Err(error.locate($day, input)),
+                }
+            }
+
+            fn solve_1(
+                &self,
+                $puzzle: &$crate::solver::Puzzle,
+                $cancel: &$crate::solver::Cancel,
+            ) -> Result<$crate::answers::Answer, $crate::error::SolveError> {
+                let $puzzle = $crate::solver::downcast(parse, $puzzle);
+                $crate::solver::Solved::into_result(solve_1 $args)
+            }
+
+            fn solve_2(
+                &self,
+                $puzzle: &$crate::solver::Puzzle,
+                $cancel: &$crate::solver::Cancel,
+            ) -> Result<$crate::answers::Answer, $crate::error::SolveError> {
+                let $puzzle = $crate::solver::downcast(parse, $puzzle);
+                $crate::solver::Solved::into_result(solve_2 $args)
+            }
+
+            fn configure(
+                &self,
+                puzzle: &mut $crate::solver::Puzzle,
+                name: &str,
+                value: &str,
+            ) -> Result<(), String> {
+                $crate::solver::solution!(@configure $day, puzzle, name, value, $configurable)
+            }
+        }
+    };
+}
+
+pub(crate) use solution;
+
+pub static SOLVERS: &[&dyn Solver] = &[
+    &day1::Solution,
+    &day2::Solution,
+    &day3::Solution,
+    &day4::Solution,
+    &day5::Solution,
+    &day6::Solution,
+    &day7::Solution,
+    &day8::Solution,
+    &day9::Solution,
+    &day10::Solution,
+    &day11::Solution,
+    &day12::Solution,
+    &day13::Solution,
+    &day14::Solution,
+    &day15::Solution,
+    &day16::Solution,
+    &day17::Solution,
+    &day18::Solution,
+    &day19::Solution,
+    &day20::Solution,
+    &day21::Solution,
+    &day22::Solution,
+    &day23::Solution,
+    &day24::Solution,
+    &day25::Solution,
+];
+
+pub fn find(day: u8) -> Option<&'static dyn Solver> {
+    SOLVERS.iter().find(|solver| solver.day() == day).copied()
+}
+
+thread_local! {
+    static ISOLATED: Cell<bool> = const { Cell::new(false) };
+    static PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Runs one parse or solve, turning a panic into an error with its message
+/// and location so that the other parts keep running. Panics inside isolated
+/// calls are not printed by the panic hook.
+pub fn isolate<T>(call: impl FnOnce() -> T) -> Result<T, String> {
+    static HOOK: Once = Once::new();
+
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if !ISOLATED.get() {
+                return default(info);
+            }
+
+            let message = info.payload_as_str().unwrap_or("panicked");
+            let message = match info.location() {
+                Some(location) => format!("{} at {}", message, location),
+                None => message.to_string(),
+            };
+
+            PANIC.set(Some(message));
+        }));
+    });
+
+    ISOLATED.set(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(call));
+    ISOLATED.set(false);
+
+    result.map_err(|_| PANIC.take().unwrap_or_else(|| "panicked".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::{downcast, isolate, Cancel, Solver, DAYS, SOLVERS};
+
+    mod faulty {
+        use crate::{answers::Answer, error::SolveError};
+
+        solution!(1, "Faulty");
+
+        pub fn parse(input: &str) -> Result<u32, SolveError> {
+            Ok(input.parse().unwrap())
+        }
+
+        pub fn solve_1(puzzle: &u32) -> Answer {
+            (puzzle * 2).into()
+        }
+
+        pub fn solve_2(_puzzle: &u32) -> Answer {
+            panic!("no solution for part {}", 2)
+        }
+    }
+
+    #[test]
+    fn panics_are_isolated() {
+        let solver = faulty::Solution;
+        let cancel = Cancel::never();
+
+        let puzzle = isolate(|| solver.parse("21")).unwrap().unwrap();
+
+        assert_eq!(downcast(faulty::parse, puzzle.as_ref()), &21);
+        assert_eq!(
+            isolate(|| solver.solve(1, puzzle.as_ref(), &cancel)),
+            Ok(Ok(42u32.into()))
+        );
+
+        let error = isolate(|| solver.parse("x")).err().unwrap();
+        assert!(error.starts_with("called `Result::unwrap()` on an `Err` value"));
+        assert!(error.contains("src/solver.rs:"));
+
+        let error = isolate(|| solver.solve(2, puzzle.as_ref(), &cancel)).unwrap_err();
+        assert!(error.starts_with("no solution for part 2 at src/solver.rs:"));
+    }
+
+    #[test]
+    fn cancel_after_deadline() {
+        assert!(!Cancel::never().is_cancelled());
+        assert!(!Cancel::after(Duration::from_secs(3600)).is_cancelled());
+        assert!(!Cancel::after(Duration::MAX).is_cancelled());
+
+        let cancel = Cancel::after(Duration::from_millis(1));
+        thread::sleep(Duration::from_millis(100));
+        assert!(cancel.is_cancelled());
+    }
+
+    #[test]
+    fn solvers_registered_in_order() {
+        let days: Vec<u8> = SOLVERS.iter().map(|solver| solver.day()).collect();
+
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(days.iter().all(|day| (1..=DAYS).contains(day)));
+        assert_eq!(days.len(), DAYS as usize);
+    }
+
+    #[test]
+    fn malformed_input_is_located() {
+        let solver = super::find(4).unwrap();
+        let error = solver.parse("2-4,6-8\n2-4;6-8\n").err().unwrap();
+
+        assert_eq!((error.day, error.line, error.column), (4, 2, 1));
+        assert_eq!(error.text, "2-4;6-8");
+
+        let error = solver.parse("2-4,6-8\n2-4,6-x\n").err().unwrap();
+
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.expected, "a number");
+    }
+
+    #[test]
+    fn non_ascii_input_is_located() {
+        let error = super::find(3).unwrap().parse("abcé\n").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 4));
+        assert_eq!(error.text, "é");
+
+        let error = super::find(17).unwrap().parse("<>→<\n").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 3));
+        assert_eq!(error.text, "→");
+    }
+
+    #[test]
+    fn impossible_moves_are_rejected() {
+        let solver = super::find(5).unwrap();
+
+        let error = solver.parse("[A] [B\n 1   2\n").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 5));
+        assert_eq!(error.text, "[B");
+
+        let crates = "[A]    \n[B] [C]\n 1   2\n\n";
+        let error = solver
+            .parse(&format!(
+                "{}move 1 from 2 to 1\nmove 2 from 2 to 1\n",
+                crates
+            ))
+            .err()
+            .unwrap();
+        assert_eq!((error.line, error.column), (6, 6));
+        assert_eq!(error.expected, "at most 0 crates");
+
+        let error = solver
+            .parse(&format!("{}move 1 from 2 to 2\n", crates))
+            .err()
+            .unwrap();
+        assert_eq!(error.expected, "a stack other than the one to move from");
+    }
+
+    #[test]
+    fn unreachable_valves_are_located() {
+        let solver = super::find(16).unwrap();
+        let error = solver
+            .parse(concat!(
+                "Valve AA has flow rate=0; tunnels lead to valves BB\n",
+                "Valve BB has flow rate=5; tunnel leads to valve AA\n",
+                "Valve CC has flow rate=3; tunnel leads to valve AA\n",
+            ))
+            .err()
+            .unwrap();
+
+        assert_eq!((error.line, error.column), (3, 7));
+        assert_eq!(error.expected, "a valve reachable from AA");
+
+        let error = solver
+            .parse("Valve BB has flow rate=5; tunnel leads to valve BB\n")
+            .err()
+            .unwrap();
+        assert_eq!(error.expected, "a valve named `AA`");
+    }
+
+    #[test]
+    fn undefined_names_are_located() {
+        let solver = super::find(21).unwrap();
+        let error = solver.parse("root: abcd + efgh\nabcd: 5\n").err().unwrap();
+
+        assert_eq!((error.day, error.line, error.column), (21, 1, 14));
+        assert_eq!(error.text, "efgh");
+        assert_eq!(error.expected, "the name of a defined monkey");
+
+        let error = solver.parse("abcd: 5\n").err().unwrap();
+        assert_eq!(error.expected, "a monkey named `root`");
+
+        let error = solver
+            .parse("root: abcd + abcd\nabcd: root * efgh\nefgh: 2\n")
+            .err()
+            .unwrap();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.expected, "a monkey not waiting on itself");
+
+        let error = solver
+            .parse("root: abcd + abcd\nabcd: 5\nabcd: 6\n")
+            .err()
+            .unwrap();
+        assert_eq!((error.line, error.column), (3, 1));
+
+        let error = solver.parse("root: 5\n").err().unwrap();
+        assert_eq!(error.expected, "two monkeys added together");
+    }
+
+    #[test]
+    fn unsolvable_input_is_rejected() {
+        let signals = super::find(6).unwrap();
+
+        let error = signals.parse("abcd\n").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 5));
+        assert_eq!(error.expected, "at least 14 characters");
+
+        let error = signals.parse("abcdefghijklmabcdefghijklm\n").err().unwrap();
+        assert_eq!(error.expected, "14 different characters in a row");
+
+        let error = super::find(7)
+            .unwrap()
+            .parse("$ cd /\n$ ls\n70000001 a.txt\n")
+            .err()
+            .unwrap();
+        assert_eq!(error.expected, "files of at most 70000000 bytes in total");
+
+        for name in [".", "..", "", "/", "a/.."] {
+            let input = format!("$ cd /\n$ ls\ndir {}\n", name);
+            let error = super::find(7).unwrap().parse(&input).err().unwrap();
+
+            assert_eq!((error.line, error.column), (3, 5));
+            assert_eq!(error.expected, "a directory name");
+        }
+    }
+}
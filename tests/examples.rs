@@ -0,0 +1,203 @@
+//! Solves every example case listed in `example_input/cases.txt` as a test of
+//! its own, reporting all mismatches before failing. Arguments filter the
+//! cases by name, like they do for the other tests.
+
+use std::{env, fs, path::Path, process};
+
+use aoc2022::{
+    input::EXAMPLE_DIR,
+    solver::{self, Cancel},
+};
+
+const CASES: &str = "cases.txt";
+
+struct Case {
+    line: usize,
+    input: String,
+    day: u8,
+    part: u8,
+    expected: String,
+    parameters: Vec<(String, String)>,
+}
+
+impl Case {
+    /// Cases added by `new-day` before their answer is known.
+    fn is_ignored(&self) -> bool {
+        self.expected == "?"
+    }
+
+    fn name(&self) -> String {
+        format!("{} part {}", self.input, self.part)
+    }
+
+    /// Solves the case, describing how it went wrong otherwise.
+    fn run(&self, directory: &Path) -> Result<(), String> {
+        let input = fs::read_to_string(directory.join(&self.input))
+            .map_err(|error| format!("cannot read {}: {}", self.input, error))?;
+        let solver =
+            solver::find(self.day).ok_or_else(|| format!("day {} is not implemented", self.day))?;
+
+        let mut puzzle = solver.parse(&input).map_err(|error| error.diagnostic())?;
+
+        for (name, value) in &self.parameters {
+            solver.configure(puzzle.as_mut(), name, value)?;
+        }
+
+        let answer =
+            solver::isolate(|| solver.solve(self.part, puzzle.as_ref(), &Cancel::never()))?
+                .map_err(|error| error.locate(self.day, &input).diagnostic())?;
+
+        if answer == self.expected {
+            return Ok(());
+        }
+
+        let multiline = answer.is_multiline() || self.expected.contains('\n');
+
+        Err(match multiline {
+            true => format!("expected:\n{}\nfound:\n{}", self.expected, answer),
+            false => format!("expected `{}`, found `{}`", self.expected, answer),
+        })
+    }
+}
+
+fn parse_case(directory: &Path, line: usize, text: &str) -> Result<Case, String> {
+    let mut fields = text.split_whitespace();
+    let mut field = |name: &str| {
+        fields
+            .next()
+            .ok_or_else(|| format!("line {}: missing {}", line, name))
+    };
+
+    let input = field("input")?.to_string();
+    let part = field("part")?;
+    let expected = field("answer")?;
+
+    let day = input
+        .strip_prefix("day")
+        .map(|name| name.split(|char: char| !char.is_ascii_digit()).next())
+        .and_then(|day| day?.parse().ok())
+        .ok_or_else(|| format!("line {}: `{}` is no dayN input", line, input))?;
+
+    let part = match part {
+        "1" => 1,
+        "2" => 2,
+        _ => return Err(format!("line {}: invalid part `{}`", line, part)),
+    };
+
+    let expected = match expected.strip_prefix('<') {
+        Some(file) => fs::read_to_string(directory.join(file))
+            .map_err(|error| format!("line {}: cannot read {}: {}", line, file, error))?
+            .trim_end_matches(['\n', '\r'])
+            .to_string(),
+        None => expected.to_string(),
+    };
+
+    let parameters = fields
+        .map(|parameter| match parameter.split_once('=') {
+            Some((name, value)) => Ok((name.to_string(), value.to_string())),
+            None => Err(format!(
+                "line {}: invalid parameter `{}`, expected `name=value`",
+                line, parameter
+            )),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Case {
+        line,
+        input,
+        day,
+        part,
+        expected,
+        parameters,
+    })
+}
+
+fn parse_cases(directory: &Path) -> Result<Vec<Case>, String> {
+    let cases = fs::read_to_string(directory.join(CASES))
+        .map_err(|error| format!("cannot read {}: {}", CASES, error))?;
+
+    cases
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty() && !text.starts_with('#'))
+        .map(|(index, text)| parse_case(directory, index + 1, text))
+        .collect()
+}
+
+fn main() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join(EXAMPLE_DIR);
+    let args: Vec<String> = env::args().skip(1).collect();
+    let filters: Vec<&String> = args.iter().filter(|arg| !arg.starts_with('-')).collect();
+
+    let cases = match parse_cases(&directory) {
+        Ok(cases) => cases,
+        Err(error) => {
+            eprintln!("error: {}: {}", CASES, error);
+            process::exit(101);
+        }
+    };
+
+    let cases: Vec<Case> = cases
+        .into_iter()
+        .filter(|case| {
+            filters.is_empty() || filters.iter().any(|filter| case.name().contains(*filter))
+        })
+        .collect();
+
+    if args.iter().any(|arg| arg == "--list") {
+        for case in &cases {
+            println!("{}: test", case.name());
+        }
+
+        return;
+    }
+
+    println!("\nrunning {} examples", cases.len());
+
+    let mut failures = vec![];
+    let mut ignored = 0;
+
+    for case in &cases {
+        if case.is_ignored() {
+            println!("example {} ... ignored", case.name());
+            ignored += 1;
+            continue;
+        }
+
+        match case.run(&directory) {
+            Ok(()) => println!("example {} ... ok", case.name()),
+            Err(error) => {
+                println!("example {} ... FAILED", case.name());
+                failures.push((case, error));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+
+        for (case, error) in &failures {
+            println!(
+                "\n---- {} ({} line {}) ----\n{}",
+                case.name(),
+                CASES,
+                case.line,
+                error.trim_end()
+            );
+        }
+    }
+
+    let result = if failures.is_empty() { "ok" } else { "FAILED" };
+
+    println!(
+        "\nexample result: {}. {} passed; {} failed; {} ignored\n",
+        result,
+        cases.len() - failures.len() - ignored,
+        failures.len(),
+        ignored
+    );
+
+    if !failures.is_empty() {
+        process::exit(101);
+    }
+}